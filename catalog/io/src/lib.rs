@@ -18,10 +18,31 @@ impl Metadata for CatalogMetadata {
 #[derive(Debug, Default, Encode, Decode, TypeInfo)]
 pub struct CatalogState {
     pub admin: ActorId,
+    pub pending_admin: Option<ActorId>,
+    pub part_managers: Vec<ActorId>,
+    pub equippable_managers: Vec<ActorId>,
+    pub dependent_collections: Vec<ActorId>,
     pub base_type: String,
     pub symbol: String,
     pub parts: Vec<(PartId, Part)>,
     pub is_equippable_to_all: Vec<PartId>,
+    pub equippable_tokens: Vec<(PartId, Vec<CollectionAndToken>)>,
+    pub part_revisions: Vec<(PartId, u32)>,
+    pub is_locked: bool,
+    pub locked_equippable_parts: Vec<PartId>,
+    pub dependents: Vec<(PartId, Vec<CollectionId>)>,
+}
+
+/// Roles granted by the admin.
+#[derive(Debug, Clone, Copy, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub enum CatalogRole {
+    /// Allows to add, update and remove parts.
+    PartManager,
+    /// Allows to change equippable lists of slot parts.
+    EquippableManager,
+    /// Marks the collection whose registered dependencies keep the parts from being removed
+    /// without the `force` flag.
+    DependentCollection,
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
@@ -55,10 +76,60 @@ pub struct SlotPart {
     pub metadata_uri: String,
 }
 
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, Eq, PartialEq)]
+pub struct TextPart {
+    /// An optional zIndex of base part layer.
+    pub z: Option<ZIndex>,
+
+    /// The text rendered by the part.
+    pub text: String,
+
+    /// The font family of the text.
+    pub font: String,
+
+    /// The font size of the text in pixels.
+    pub font_size: u32,
+
+    /// The colour of the text in hex format, `#RRGGBB` or `#RRGGBBAA`.
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, Eq, PartialEq)]
+pub struct PalettePart {
+    /// An optional zIndex of base part layer.
+    pub z: Option<ZIndex>,
+
+    /// Colours of the palette in hex format, `#RRGGBB` or `#RRGGBBAA`.
+    pub colors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Encode, Decode, TypeInfo, Eq, PartialEq)]
+pub struct AnimationPart {
+    /// An optional zIndex of base part layer.
+    pub z: Option<ZIndex>,
+
+    /// The metadata URI of the animation frames.
+    pub metadata_uri: String,
+
+    /// The number of frames in the animation.
+    pub frame_count: u32,
+
+    /// The duration of a single frame in milliseconds.
+    pub frame_duration: u32,
+
+    /// Indicates whether the animation is repeated.
+    pub looped: bool,
+}
+
+/// New variants must be added to the end of the enum
+/// to keep the SCALE encoding of the existing parts.
 #[derive(Debug, Clone, Decode, Encode, TypeInfo, Eq, PartialEq)]
 pub enum Part {
     Fixed(FixedPart),
     Slot(SlotPart),
+    Text(TextPart),
+    Palette(PalettePart),
+    Animation(AnimationPart),
 }
 
 #[derive(Debug, Decode, Encode, TypeInfo)]
@@ -74,7 +145,7 @@ pub enum CatalogAction {
     /// Adds parts to base contract.
     ///
     /// # Requirements:
-    /// * The `msg::source()` must be the contract issuer.
+    /// * The `msg::source()` must be the contract admin or a part manager.
     /// * `PartId` must be unique.
     ///
     /// # Arguments:
@@ -83,10 +154,30 @@ pub enum CatalogAction {
     /// On success replies `[BaseEvent::PartsAdded]`.
     AddParts(BTreeMap<PartId, Part>),
 
+    /// Updates the metadata URI and zIndex of the part.
+    /// The type of the part is preserved and its revision is incremented.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or a part manager.
+    /// * The part with indicated `part_id` must exist.
+    /// * The part must have a metadata URI, text and palette parts can not be updated.
+    ///
+    /// # Arguments:
+    /// * `part_id`: the Part Id.
+    /// * `metadata_uri`: the new metadata URI of the part.
+    /// * `z`: the new zIndex of the part.
+    ///
+    /// On success replies `[CatalogReply::PartUpdated]`.
+    UpdatePart {
+        part_id: PartId,
+        metadata_uri: String,
+        z: Option<ZIndex>,
+    },
+
     /// Adds equippable to slot part.
     ///
     /// # Requirements:
-    /// * The `msg::source()` must be the contract admin.
+    /// * The `msg::source()` must be the contract admin or an equippable manager.
     /// * The indicated collection contract must be RMRK contract.
     /// * The token from indicated collections must have composable resource that refers to that base.
     ///
@@ -102,19 +193,24 @@ pub enum CatalogAction {
     /// Removes parts from the base.
     ///
     /// # Requirements:
-    /// * The `msg::source()` must be the contract issuer.
+    /// * The `msg::source()` must be the contract admin or a part manager.
     /// * The parts with indicated PartIds must exist.
+    /// * The parts must not have dependent collections unless `force` is set.
+    ///   Only the collections with the `DependentCollection` role are taken into account.
     ///
     /// # Arguments:
-    /// * `Vec<PartId>`: Part IDs to be removed.
+    /// * `part_ids`: Part IDs to be removed.
+    /// * `force`: removes the parts even if collections depend on them.
     ///
-    /// On success replies `[BaseEvent::PartsRemoved]`.
-    RemoveParts(Vec<PartId>),
+    /// On success replies `[CatalogReply::PartsRemoved]`.
+    /// If the parts have dependent collections and `force` is not set,
+    /// replies `[CatalogError::PartsHaveDependents]` with the dependent collections of each part.
+    RemoveParts { part_ids: Vec<PartId>, force: bool },
 
     /// Removes equippable from the slot part.
     ///
     /// # Requirements:
-    /// * The `msg::source()` must be the contract issuer.
+    /// * The `msg::source()` must be the contract admin or an equippable manager.
     /// * Indicated equippable must exist.
     ///
     /// # Arguments:
@@ -126,6 +222,30 @@ pub enum CatalogAction {
         collection_id: CollectionId,
     },
 
+    /// Locks the catalog.
+    /// After that parts and equippable lists can no longer be added, removed or changed.
+    /// Locking the locked catalog has no effect.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin.
+    ///
+    /// On success replies `[CatalogReply::CatalogLocked]`.
+    LockCatalog,
+
+    /// Locks the equippable list of the slot part.
+    /// After that the equippable list of the part can no longer be changed and the part can not be removed,
+    /// while other parts remain editable.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin.
+    /// * The part must exist and be a slot part.
+    ///
+    /// # Arguments:
+    /// * `part_id`: the Part Id.
+    ///
+    /// On success replies `[CatalogReply::EquippableListLocked]`.
+    LockEquippableList { part_id: PartId },
+
     /// Checks whether the part exists in the Base.
     ///
     /// # Arguments:
@@ -134,24 +254,166 @@ pub enum CatalogAction {
     /// On success replies `[BaseEvent::Part]`.
     CheckPart(PartId),
 
+    /// Checks whether all indicated parts exist in the catalog.
+    ///
+    /// # Arguments:
+    /// * `Vec<PartId>`: the Part Ids.
+    ///
+    /// On success replies `[CatalogReply::Parts]` with the parts in the same order as the indicated Part Ids.
+    CheckParts(Vec<PartId>),
+
     /// Checks whether the token from specified collection is in equippable list.
+    /// The token is equippable if the part is equippable to all,
+    /// if its collection is in the equippable list of the slot part
+    /// or if the token itself is in the token-level equippable list of the part.
     ///
     /// # Arguments:
     /// * `part_id`: the Part Id.
     /// * `collection_id`: an address of RMRK contract.
-    /// * `token_id`: the id of the token in RMRK contract.
+    /// * `token_id`: the id of the token in RMRK contract (if any).
     ///
-    /// On success replies `[BaseEvent::Part]`.
+    /// On success replies `[CatalogReply::InEquippableList]` or `[CatalogReply::NotInEquippableList]`.
     CheckEquippable {
         part_id: PartId,
         collection_id: CollectionId,
+        token_id: Option<TokenId>,
     },
-    SetEquippableToAll {
-        part_id: PartId,
-    },
-    ResetEquippableAddress {
+
+    /// Checks whether the tokens from specified collections are in equippable lists of the slot parts,
+    /// the same way as `[CatalogAction::CheckEquippable]` does.
+    /// Allows a client to validate a whole outfit with a single message.
+    ///
+    /// # Arguments:
+    /// * `Vec<(PartId, CollectionId, Option<TokenId>)>`: the Part Id, an address of RMRK contract
+    /// and the id of the token in RMRK contract (if any).
+    ///
+    /// On success replies `[CatalogReply::EquippableBatch]` with the result of each check in the same order.
+    CheckEquippableBatch(Vec<(PartId, CollectionId, Option<TokenId>)>),
+
+    /// Makes the slot part equippable by any collection.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or an equippable manager.
+    /// * The part must exist and be a slot part.
+    ///
+    /// # Arguments:
+    /// * `part_id`: the Part Id.
+    ///
+    /// On success replies `[CatalogReply::EquippableToAllSet]`.
+    SetEquippableToAll { part_id: PartId },
+
+    /// Sets the equippable list of the slot part.
+    /// `EquippableList::All` makes the part equippable by any collection and clears its token-level list,
+    /// `EquippableList::Custom` replaces the token-level equippable list of the part
+    /// and makes the part no longer equippable by any collection.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or an equippable manager.
+    /// * The part must exist and be a slot part.
+    ///
+    /// # Arguments:
+    /// * `part_id`: the Part Id.
+    /// * `list`: the equippable list.
+    ///
+    /// On success replies `[CatalogReply::EquippableListSet]`.
+    SetEquippableList {
         part_id: PartId,
+        list: EquippableList,
     },
+
+    /// Makes the slot part equippable only by collections from its equippable list.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or an equippable manager.
+    /// * The part must exist and be a slot part.
+    ///
+    /// # Arguments:
+    /// * `part_id`: the Part Id.
+    ///
+    /// On success replies `[CatalogReply::EquippableToAllUnset]`.
+    UnsetEquippableToAll { part_id: PartId },
+
+    /// Removes all equippable addresses from the slot part
+    /// and makes it no longer equippable by any collection.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or an equippable manager.
+    /// * The part must exist and be a slot part.
+    ///
+    /// # Arguments:
+    /// * `part_id`: the Part Id.
+    ///
+    /// On success replies `[CatalogReply::EqippableAddressesReset]`.
+    ResetEquippableAddress { part_id: PartId },
+
+    /// Proposes a new admin of the catalog.
+    /// The transfer is completed only after the new admin accepts it with `AcceptAdmin`.
+    /// Proposing another admin replaces the pending one.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin.
+    ///
+    /// # Arguments:
+    /// * `new_admin`: the address of the proposed admin.
+    ///
+    /// On success replies `[CatalogReply::AdminTransferProposed]`.
+    TransferAdmin { new_admin: ActorId },
+
+    /// Accepts the admin role proposed with `TransferAdmin`.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the pending admin.
+    ///
+    /// On success replies `[CatalogReply::AdminTransferred]`.
+    AcceptAdmin,
+
+    /// Grants the role to the account.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin.
+    ///
+    /// # Arguments:
+    /// * `role`: the granted role.
+    /// * `account`: the address that receives the role.
+    ///
+    /// On success replies `[CatalogReply::RoleGranted]`.
+    GrantRole { role: CatalogRole, account: ActorId },
+
+    /// Revokes the role from the account.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin.
+    ///
+    /// # Arguments:
+    /// * `role`: the revoked role.
+    /// * `account`: the address that loses the role.
+    ///
+    /// On success replies `[CatalogReply::RoleRevoked]`.
+    RevokeRole { role: CatalogRole, account: ActorId },
+
+    /// Registers the `msg::source()` as a collection depending on the parts.
+    /// If the collection has the `DependentCollection` role,
+    /// the parts can only be removed with the `force` flag.
+    /// A collection can only register and unregister itself,
+    /// so it can not drop the registrations of other collections.
+    ///
+    /// # Requirements:
+    /// * The parts with indicated PartIds must exist.
+    ///
+    /// # Arguments:
+    /// * `Vec<PartId>`: Part IDs the collection depends on.
+    ///
+    /// On success replies `[CatalogReply::DependentRegistered]`.
+    RegisterDependent(Vec<PartId>),
+
+    /// Removes the `msg::source()` from the dependent collections of the parts.
+    /// The registrations of other collections are left untouched.
+    ///
+    /// # Arguments:
+    /// * `Vec<PartId>`: Part IDs the collection no longer depends on.
+    ///
+    /// On success replies `[CatalogReply::DependentUnregistered]`.
+    UnregisterDependent(Vec<PartId>),
 }
 
 #[derive(Debug, Decode, Encode, TypeInfo, Clone, PartialEq)]
@@ -168,9 +430,31 @@ pub enum CatalogReply {
         collection_id: CollectionId,
     },
     Part(Part),
+    Parts(Vec<Part>),
     InEquippableList,
     NotInEquippableList,
+    EquippableBatch(Vec<bool>),
     EquippableToAllSet,
+    EquippableToAllUnset,
+    PartUpdated {
+        part_id: PartId,
+        revision: u32,
+    },
+    CatalogLocked,
+    EquippableListLocked(PartId),
+    EquippableListSet(PartId),
+    AdminTransferProposed(ActorId),
+    AdminTransferred(ActorId),
+    RoleGranted {
+        role: CatalogRole,
+        account: ActorId,
+    },
+    RoleRevoked {
+        role: CatalogRole,
+        account: ActorId,
+    },
+    DependentRegistered(Vec<PartId>),
+    DependentUnregistered(Vec<PartId>),
 }
 
 #[derive(Debug, Decode, Encode, TypeInfo)]
@@ -182,4 +466,6 @@ pub enum CatalogError {
     PartDoesNotExist,
     WrongPartFormat,
     NotAllowedToCall,
+    CatalogLocked,
+    PartsHaveDependents(Vec<(PartId, Vec<CollectionId>)>),
 }
@@ -11,47 +11,213 @@ pub struct Catalog {
     /// Mapping from `PartId` to fixed or slot `Part`.
     pub parts: HashMap<PartId, Part>,
 
-    /// mapping of uint64 `partId` to boolean flag,
-    /// indicating that a given `Part` can be equippable by any address
-    pub is_equippable_to_all: Vec<PartId>,
+    /// Set of `PartId`s, indicating that a given `Part` can be equippable by any address
+    pub is_equippable_to_all: HashSet<PartId>,
+
+    /// Mapping from slot `PartId` to the tokens that can be equipped into it
+    /// in addition to the collections from the equippable list of the part.
+    pub equippable_tokens: HashMap<PartId, BTreeSet<CollectionAndToken>>,
+
+    /// Mapping from `PartId` to the revision of the part, incremented on each update.
+    pub part_revisions: HashMap<PartId, u32>,
+
+    /// Indicates that parts and equippable lists can no longer be changed.
+    pub is_locked: bool,
+
+    /// Set of slot `PartId`s whose equippable lists can no longer be changed.
+    pub locked_equippable_parts: HashSet<PartId>,
+
+    /// Mapping from `PartId` to the collections whose assets reference the part.
+    pub dependents: HashMap<PartId, BTreeSet<CollectionId>>,
+
+    /// Accounts allowed to add, update and remove parts.
+    pub part_managers: HashSet<ActorId>,
+
+    /// Accounts allowed to change equippable lists.
+    pub equippable_managers: HashSet<ActorId>,
+
+    /// Collections whose registered dependencies keep the parts from being removed.
+    pub dependent_collections: HashSet<ActorId>,
 }
 
 impl Catalog {
+    pub fn grant_role(
+        &mut self,
+        role: CatalogRole,
+        account: ActorId,
+    ) -> Result<CatalogReply, CatalogError> {
+        self.role_members_mut(role).insert(account);
+        Ok(CatalogReply::RoleGranted { role, account })
+    }
+
+    pub fn revoke_role(
+        &mut self,
+        role: CatalogRole,
+        account: ActorId,
+    ) -> Result<CatalogReply, CatalogError> {
+        self.role_members_mut(role).remove(&account);
+        Ok(CatalogReply::RoleRevoked { role, account })
+    }
+
+    pub fn has_role(&self, role: CatalogRole, account: &ActorId) -> bool {
+        match role {
+            CatalogRole::PartManager => self.part_managers.contains(account),
+            CatalogRole::EquippableManager => self.equippable_managers.contains(account),
+            CatalogRole::DependentCollection => self.dependent_collections.contains(account),
+        }
+    }
+
+    fn role_members_mut(&mut self, role: CatalogRole) -> &mut HashSet<ActorId> {
+        match role {
+            CatalogRole::PartManager => &mut self.part_managers,
+            CatalogRole::EquippableManager => &mut self.equippable_managers,
+            CatalogRole::DependentCollection => &mut self.dependent_collections,
+        }
+    }
+
     pub fn add_parts(
         &mut self,
         parts: BTreeMap<PartId, Part>,
     ) -> Result<CatalogReply, CatalogError> {
+        self.check_not_locked()?;
         if parts.is_empty() {
             return Err(CatalogError::ZeroLengthPassed);
         }
-        for (part_id, part) in parts.clone() {
-            if part_id == 0 {
+        // all the parts are checked before any of them is added,
+        // so an invalid part doesn't leave the batch partially added
+        for (part_id, part) in &parts {
+            if *part_id == 0 {
                 return Err(CatalogError::PartIdCantBeZero);
             }
-            if self.parts.insert(part_id, part).is_some() {
+            check_part_format(part)?;
+            // the existing part is changed only through `UpdatePart`
+            if self.parts.contains_key(part_id) {
                 return Err(CatalogError::PartAlreadyExists);
             }
         }
+        for (part_id, part) in parts.clone() {
+            self.parts.insert(part_id, part);
+            self.part_revisions.insert(part_id, 0);
+        }
         Ok(CatalogReply::PartsAdded(parts))
     }
 
-    pub fn remove_parts(&mut self, parts: Vec<PartId>) -> Result<CatalogReply, CatalogError> {
+    pub fn remove_parts(
+        &mut self,
+        parts: Vec<PartId>,
+        force: bool,
+    ) -> Result<CatalogReply, CatalogError> {
+        self.check_not_locked()?;
         if parts.is_empty() {
             return Err(CatalogError::ZeroLengthPassed);
         }
+        let mut parts_with_dependents = Vec::new();
         for part_id in &parts {
-            if self.parts.remove(part_id).is_none() {
-                return Err(CatalogError::PartDoesNotExist);
+            get_part(&self.parts, *part_id)?;
+            self.check_equippable_not_locked(*part_id)?;
+            // anyone can register as a dependent,
+            // so only the collections approved by the admin keep the part
+            let collections: Vec<CollectionId> = self
+                .dependents
+                .get(part_id)
+                .into_iter()
+                .flatten()
+                .filter(|collection| self.dependent_collections.contains(*collection))
+                .copied()
+                .collect();
+            if !collections.is_empty() {
+                parts_with_dependents.push((*part_id, collections));
             }
         }
+        if !force && !parts_with_dependents.is_empty() {
+            return Err(CatalogError::PartsHaveDependents(parts_with_dependents));
+        }
+        for part_id in &parts {
+            self.parts.remove(part_id);
+            self.is_equippable_to_all.remove(part_id);
+            self.equippable_tokens.remove(part_id);
+            self.part_revisions.remove(part_id);
+            self.dependents.remove(part_id);
+        }
         Ok(CatalogReply::PartsRemoved(parts))
     }
 
+    pub fn register_dependent(
+        &mut self,
+        collection_id: CollectionId,
+        parts: Vec<PartId>,
+    ) -> Result<CatalogReply, CatalogError> {
+        for part_id in &parts {
+            get_part(&self.parts, *part_id)?;
+        }
+        for part_id in &parts {
+            self.dependents
+                .entry(*part_id)
+                .or_default()
+                .insert(collection_id);
+        }
+        Ok(CatalogReply::DependentRegistered(parts))
+    }
+
+    pub fn unregister_dependent(
+        &mut self,
+        collection_id: CollectionId,
+        parts: Vec<PartId>,
+    ) -> Result<CatalogReply, CatalogError> {
+        for part_id in &parts {
+            if let Some(collections) = self.dependents.get_mut(part_id) {
+                collections.remove(&collection_id);
+                if collections.is_empty() {
+                    self.dependents.remove(part_id);
+                }
+            }
+        }
+        Ok(CatalogReply::DependentUnregistered(parts))
+    }
+
+    pub fn update_part(
+        &mut self,
+        part_id: PartId,
+        metadata_uri: String,
+        z: Option<ZIndex>,
+    ) -> Result<CatalogReply, CatalogError> {
+        self.check_not_locked()?;
+        let part = get_mut_part(&mut self.parts, part_id)?;
+        match part {
+            Part::Fixed(FixedPart {
+                z: part_z,
+                metadata_uri: part_metadata_uri,
+            })
+            | Part::Slot(SlotPart {
+                z: part_z,
+                metadata_uri: part_metadata_uri,
+                ..
+            })
+            | Part::Animation(AnimationPart {
+                z: part_z,
+                metadata_uri: part_metadata_uri,
+                ..
+            }) => {
+                *part_z = z;
+                *part_metadata_uri = metadata_uri;
+            }
+            Part::Text(_) | Part::Palette(_) => return Err(CatalogError::WrongPartFormat),
+        }
+
+        let revision = self.part_revisions.entry(part_id).or_default();
+        *revision += 1;
+        Ok(CatalogReply::PartUpdated {
+            part_id,
+            revision: *revision,
+        })
+    }
+
     pub fn add_equippable_addresses(
         &mut self,
         part_id: PartId,
         collection_ids: Vec<CollectionId>,
     ) -> Result<CatalogReply, CatalogError> {
+        self.check_equippable_not_locked(part_id)?;
         if collection_ids.is_empty() {
             return Err(CatalogError::ZeroLengthPassed);
         }
@@ -76,32 +242,79 @@ impl Catalog {
         &mut self,
         part_id: PartId,
     ) -> Result<CatalogReply, CatalogError> {
-        self.is_equippable_to_all.retain(|&x| x != part_id);
+        self.check_equippable_not_locked(part_id)?;
         let part = get_mut_part(&mut self.parts, part_id)?;
         if let Part::Slot(SlotPart { equippable, .. }) = part {
             *equippable = vec![];
         } else {
             return Err(CatalogError::WrongPartFormat);
         }
+        self.is_equippable_to_all.remove(&part_id);
+        self.equippable_tokens.remove(&part_id);
 
         Ok(CatalogReply::EqippableAddressesReset)
     }
 
     pub fn set_equippable_to_all(&mut self, part_id: PartId) -> Result<CatalogReply, CatalogError> {
+        self.check_equippable_not_locked(part_id)?;
         let part = get_part(&self.parts, part_id)?;
-        if let Part::Fixed { .. } = part {
+        if !matches!(part, Part::Slot(_)) {
             return Err(CatalogError::WrongPartFormat);
         }
 
-        self.is_equippable_to_all.push(part_id);
+        self.is_equippable_to_all.insert(part_id);
         Ok(CatalogReply::EquippableToAllSet)
     }
 
+    pub fn set_equippable_list(
+        &mut self,
+        part_id: PartId,
+        list: EquippableList,
+    ) -> Result<CatalogReply, CatalogError> {
+        self.check_equippable_not_locked(part_id)?;
+        let part = get_part(&self.parts, part_id)?;
+        if !matches!(part, Part::Slot(_)) {
+            return Err(CatalogError::WrongPartFormat);
+        }
+
+        // the new list replaces the previous one
+        match list {
+            EquippableList::All => {
+                self.is_equippable_to_all.insert(part_id);
+                self.equippable_tokens.remove(&part_id);
+            }
+            EquippableList::Custom(tokens) => {
+                self.is_equippable_to_all.remove(&part_id);
+                if tokens.is_empty() {
+                    self.equippable_tokens.remove(&part_id);
+                } else {
+                    self.equippable_tokens.insert(part_id, tokens);
+                }
+            }
+        }
+        Ok(CatalogReply::EquippableListSet(part_id))
+    }
+
+    pub fn unset_equippable_to_all(
+        &mut self,
+        part_id: PartId,
+    ) -> Result<CatalogReply, CatalogError> {
+        self.check_equippable_not_locked(part_id)?;
+        let part = get_part(&self.parts, part_id)?;
+        if !matches!(part, Part::Slot(_)) {
+            return Err(CatalogError::WrongPartFormat);
+        }
+
+        self.is_equippable_to_all.remove(&part_id);
+        Ok(CatalogReply::EquippableToAllUnset)
+    }
+
     pub fn remove_equippable(
         &mut self,
         part_id: PartId,
         collection_id: CollectionId,
     ) -> Result<CatalogReply, CatalogError> {
+        self.check_equippable_not_locked(part_id)?;
         let part = get_mut_part(&mut self.parts, part_id)?;
         if let Part::Slot(SlotPart { equippable, .. }) = part {
             equippable.retain(|&x| x != collection_id);
@@ -115,24 +328,82 @@ impl Catalog {
         })
     }
 
+    pub fn lock_catalog(&mut self) -> Result<CatalogReply, CatalogError> {
+        // locking the locked catalog is a no-op
+        self.is_locked = true;
+        Ok(CatalogReply::CatalogLocked)
+    }
+
+    pub fn lock_equippable_list(&mut self, part_id: PartId) -> Result<CatalogReply, CatalogError> {
+        self.check_equippable_not_locked(part_id)?;
+        let part = get_part(&self.parts, part_id)?;
+        if !matches!(part, Part::Slot(_)) {
+            return Err(CatalogError::WrongPartFormat);
+        }
+
+        self.locked_equippable_parts.insert(part_id);
+        Ok(CatalogReply::EquippableListLocked(part_id))
+    }
+
+    fn check_not_locked(&self) -> Result<(), CatalogError> {
+        if self.is_locked {
+            return Err(CatalogError::CatalogLocked);
+        }
+        Ok(())
+    }
+
+    fn check_equippable_not_locked(&self, part_id: PartId) -> Result<(), CatalogError> {
+        self.check_not_locked()?;
+        if self.locked_equippable_parts.contains(&part_id) {
+            return Err(CatalogError::CatalogLocked);
+        }
+        Ok(())
+    }
+
     pub fn check_part(&self, part_id: PartId) -> Result<CatalogReply, CatalogError> {
         let part = get_part(&self.parts, part_id)?;
         Ok(CatalogReply::Part(part.clone()))
     }
 
+    pub fn check_parts(&self, part_ids: Vec<PartId>) -> Result<CatalogReply, CatalogError> {
+        let parts = part_ids
+            .into_iter()
+            .map(|part_id| get_part(&self.parts, part_id).cloned())
+            .collect::<Result<Vec<Part>, CatalogError>>()?;
+        Ok(CatalogReply::Parts(parts))
+    }
+
+    pub fn check_equippable_batch(
+        &self,
+        checks: Vec<(PartId, CollectionId, Option<TokenId>)>,
+    ) -> Result<CatalogReply, CatalogError> {
+        let mut results = Vec::with_capacity(checks.len());
+        for (part_id, collection_id, token_id) in checks {
+            let reply = self.check_equippable(part_id, collection_id, token_id)?;
+            results.push(reply == CatalogReply::InEquippableList);
+        }
+        Ok(CatalogReply::EquippableBatch(results))
+    }
+
     pub fn check_equippable(
         &self,
         part_id: PartId,
         collection_id: CollectionId,
+        token_id: Option<TokenId>,
     ) -> Result<CatalogReply, CatalogError> {
-        for equippable in self.is_equippable_to_all.iter() {
-            if *equippable == part_id {
-                return Ok(CatalogReply::InEquippableList);
-            }
+        if self.is_equippable_to_all.contains(&part_id) {
+            return Ok(CatalogReply::InEquippableList);
         }
+        let in_equippable_tokens = if let Some(token_id) = token_id {
+            self.equippable_tokens
+                .get(&part_id)
+                .map_or(false, |tokens| tokens.contains(&(collection_id, token_id)))
+        } else {
+            false
+        };
         let part = get_part(&self.parts, part_id)?;
         if let Part::Slot(SlotPart { equippable, .. }) = part {
-            if equippable.iter().any(|&x| x == collection_id) {
+            if in_equippable_tokens || equippable.iter().any(|&x| x == collection_id) {
                 Ok(CatalogReply::InEquippableList)
             } else {
                 Ok(CatalogReply::NotInEquippableList)
@@ -143,6 +414,30 @@ impl Catalog {
     }
 }
 
+fn check_part_format(part: &Part) -> Result<(), CatalogError> {
+    let valid = match part {
+        Part::Text(TextPart { color, .. }) => is_hex_color(color),
+        Part::Palette(PalettePart { colors, .. }) => {
+            !colors.is_empty() && colors.iter().all(|color| is_hex_color(color))
+        }
+        Part::Animation(AnimationPart { frame_count, .. }) => *frame_count > 0,
+        Part::Fixed(_) | Part::Slot(_) => true,
+    };
+    if !valid {
+        return Err(CatalogError::WrongPartFormat);
+    }
+    Ok(())
+}
+
+fn is_hex_color(color: &str) -> bool {
+    let digits = if let Some(digits) = color.strip_prefix('#') {
+        digits
+    } else {
+        return false;
+    };
+    (digits.len() == 6 || digits.len() == 8) && digits.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 fn get_part(parts: &HashMap<PartId, Part>, part_id: PartId) -> Result<&Part, CatalogError> {
     if let Some(part) = parts.get(&part_id) {
         return Ok(part);
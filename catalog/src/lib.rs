@@ -2,13 +2,14 @@
 
 use catalog_io::*;
 use gstd::{msg, prelude::*, ActorId};
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use types::primitives::*;
 pub mod catalog;
 use catalog::Catalog;
 
 static mut CATALOG: Option<Catalog> = None;
 static mut ADMIN: Option<ActorId> = None;
+static mut PENDING_ADMIN: Option<ActorId> = None;
 
 #[no_mangle]
 extern "C" fn init() {
@@ -37,36 +38,89 @@ fn process_handle(
 ) -> Result<CatalogReply, CatalogError> {
     match action {
         CatalogAction::AddParts(parts) => {
-            only_admin()?;
+            only_role(catalog, CatalogRole::PartManager)?;
             catalog.add_parts(parts.clone())
         }
+        CatalogAction::UpdatePart {
+            part_id,
+            metadata_uri,
+            z,
+        } => {
+            only_role(catalog, CatalogRole::PartManager)?;
+            catalog.update_part(*part_id, metadata_uri.clone(), *z)
+        }
         CatalogAction::AddEquippableAddresses {
             part_id,
             collection_ids,
         } => {
-            only_admin()?;
+            only_role(catalog, CatalogRole::EquippableManager)?;
             catalog.add_equippable_addresses(*part_id, collection_ids.clone())
         }
-        CatalogAction::RemoveParts(parts) => {
-            only_admin()?;
-            catalog.remove_parts(parts.clone())
+        CatalogAction::RemoveParts { part_ids, force } => {
+            only_role(catalog, CatalogRole::PartManager)?;
+            catalog.remove_parts(part_ids.clone(), *force)
         }
         CatalogAction::RemoveEquippable {
             part_id,
             collection_id,
         } => {
-            only_admin()?;
+            only_role(catalog, CatalogRole::EquippableManager)?;
             catalog.remove_equippable(*part_id, *collection_id)
         }
+        CatalogAction::LockCatalog => {
+            only_admin()?;
+            catalog.lock_catalog()
+        }
+        CatalogAction::LockEquippableList { part_id } => {
+            only_admin()?;
+            catalog.lock_equippable_list(*part_id)
+        }
         CatalogAction::CheckPart(part_id) => catalog.check_part(*part_id),
+        CatalogAction::CheckParts(part_ids) => catalog.check_parts(part_ids.clone()),
         CatalogAction::CheckEquippable {
             part_id,
             collection_id,
-        } => catalog.check_equippable(*part_id, *collection_id),
-        CatalogAction::SetEquippableToAll { part_id } => catalog.set_equippable_to_all(*part_id),
+            token_id,
+        } => catalog.check_equippable(*part_id, *collection_id, *token_id),
+        CatalogAction::CheckEquippableBatch(checks) => {
+            catalog.check_equippable_batch(checks.clone())
+        }
+        CatalogAction::SetEquippableToAll { part_id } => {
+            only_role(catalog, CatalogRole::EquippableManager)?;
+            catalog.set_equippable_to_all(*part_id)
+        }
+        CatalogAction::SetEquippableList { part_id, list } => {
+            only_role(catalog, CatalogRole::EquippableManager)?;
+            catalog.set_equippable_list(*part_id, list.clone())
+        }
+        CatalogAction::UnsetEquippableToAll { part_id } => {
+            only_role(catalog, CatalogRole::EquippableManager)?;
+            catalog.unset_equippable_to_all(*part_id)
+        }
         CatalogAction::ResetEquippableAddress { part_id } => {
+            only_role(catalog, CatalogRole::EquippableManager)?;
             catalog.reset_equippable_addresses(*part_id)
         }
+        CatalogAction::TransferAdmin { new_admin } => {
+            only_admin()?;
+            unsafe { PENDING_ADMIN = Some(*new_admin) };
+            Ok(CatalogReply::AdminTransferProposed(*new_admin))
+        }
+        CatalogAction::AcceptAdmin => accept_admin(),
+        CatalogAction::RegisterDependent(part_ids) => {
+            catalog.register_dependent(msg::source(), part_ids.clone())
+        }
+        CatalogAction::UnregisterDependent(part_ids) => {
+            catalog.unregister_dependent(msg::source(), part_ids.clone())
+        }
+        CatalogAction::GrantRole { role, account } => {
+            only_admin()?;
+            catalog.grant_role(*role, *account)
+        }
+        CatalogAction::RevokeRole { role, account } => {
+            only_admin()?;
+            catalog.revoke_role(*role, *account)
+        }
     }
 }
 
@@ -76,6 +130,10 @@ extern "C" fn state() {
     let admin = unsafe { ADMIN.as_ref().expect("The contract is not initialized") };
     let catalog_state = CatalogState {
         admin: *admin,
+        pending_admin: unsafe { PENDING_ADMIN },
+        part_managers: catalog.part_managers.iter().copied().collect(),
+        equippable_managers: catalog.equippable_managers.iter().copied().collect(),
+        dependent_collections: catalog.dependent_collections.iter().copied().collect(),
         base_type: catalog.base_type.clone(),
         symbol: catalog.symbol.clone(),
         parts: catalog
@@ -83,7 +141,24 @@ extern "C" fn state() {
             .iter()
             .map(|(key, value)| (*key, value.clone()))
             .collect(),
-        is_equippable_to_all: catalog.is_equippable_to_all.clone(),
+        is_equippable_to_all: catalog.is_equippable_to_all.iter().copied().collect(),
+        equippable_tokens: catalog
+            .equippable_tokens
+            .iter()
+            .map(|(key, value)| (*key, value.iter().copied().collect()))
+            .collect(),
+        part_revisions: catalog
+            .part_revisions
+            .iter()
+            .map(|(key, value)| (*key, *value))
+            .collect(),
+        is_locked: catalog.is_locked,
+        locked_equippable_parts: catalog.locked_equippable_parts.iter().copied().collect(),
+        dependents: catalog
+            .dependents
+            .iter()
+            .map(|(key, value)| (*key, value.iter().copied().collect()))
+            .collect(),
     };
     msg::reply(catalog_state, 0).expect("Failed to share state");
 }
@@ -95,3 +170,22 @@ fn only_admin() -> Result<(), CatalogError> {
     }
     Ok(())
 }
+
+fn only_role(catalog: &Catalog, role: CatalogRole) -> Result<(), CatalogError> {
+    if only_admin().is_ok() || catalog.has_role(role, &msg::source()) {
+        return Ok(());
+    }
+    Err(CatalogError::NotAllowedToCall)
+}
+
+fn accept_admin() -> Result<CatalogReply, CatalogError> {
+    let pending_admin = unsafe { PENDING_ADMIN };
+    if pending_admin != Some(msg::source()) {
+        return Err(CatalogError::NotAllowedToCall);
+    }
+    unsafe {
+        ADMIN = pending_admin;
+        PENDING_ADMIN = None;
+    }
+    Ok(CatalogReply::AdminTransferred(msg::source()))
+}
@@ -0,0 +1,96 @@
+#![no_std]
+
+use catalog_io::*;
+use gmeta::metawasm;
+use gstd::prelude::*;
+use types::primitives::{CollectionId, PartId, TokenId};
+
+#[cfg(feature = "binary-vendor")]
+include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
+
+#[metawasm]
+pub mod metafns {
+    pub type State = CatalogState;
+
+    pub fn part(state: State, part_id: PartId) -> Option<Part> {
+        state
+            .parts
+            .into_iter()
+            .find(|(id, _)| id == &part_id)
+            .map(|(_, part)| part)
+    }
+
+    /// Returns at most `limit` parts sorted by their ids, starting from `offset`.
+    pub fn parts(state: State, offset: u32, limit: u32) -> Vec<(PartId, Part)> {
+        let mut parts = state.parts;
+        parts.sort_by_key(|(id, _)| *id);
+        parts
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    pub fn slot_parts(state: State) -> Vec<(PartId, SlotPart)> {
+        let mut slot_parts: Vec<(PartId, SlotPart)> = state
+            .parts
+            .into_iter()
+            .filter_map(|(id, part)| match part {
+                Part::Slot(slot_part) => Some((id, slot_part)),
+                _ => None,
+            })
+            .collect();
+        slot_parts.sort_by_key(|(id, _)| *id);
+        slot_parts
+    }
+
+    pub fn fixed_parts(state: State) -> Vec<(PartId, FixedPart)> {
+        let mut fixed_parts: Vec<(PartId, FixedPart)> = state
+            .parts
+            .into_iter()
+            .filter_map(|(id, part)| match part {
+                Part::Fixed(fixed_part) => Some((id, fixed_part)),
+                _ => None,
+            })
+            .collect();
+        fixed_parts.sort_by_key(|(id, _)| *id);
+        fixed_parts
+    }
+
+    /// Checks whether the token from the collection can be equipped into the slot part,
+    /// the same way as `CatalogAction::CheckEquippable` does.
+    /// If `token_id` is `None`, checks whether the whole collection can be equipped.
+    pub fn is_equippable(
+        state: State,
+        part_id: PartId,
+        collection_id: CollectionId,
+        token_id: Option<TokenId>,
+    ) -> bool {
+        if state.is_equippable_to_all.contains(&part_id) {
+            return true;
+        }
+        if let Some(token_id) = token_id {
+            let in_equippable_tokens = state
+                .equippable_tokens
+                .iter()
+                .any(|(id, tokens)| id == &part_id && tokens.contains(&(collection_id, token_id)));
+            if in_equippable_tokens {
+                return true;
+            }
+        }
+        matches!(
+            state.parts.iter().find(|(id, _)| id == &part_id),
+            Some((_, Part::Slot(SlotPart { equippable, .. }))) if equippable.contains(&collection_id)
+        )
+    }
+
+    pub fn equippable_collections(state: State, part_id: PartId) -> Vec<CollectionId> {
+        if let Some((_, Part::Slot(SlotPart { equippable, .. }))) =
+            state.parts.into_iter().find(|(id, _)| id == &part_id)
+        {
+            equippable
+        } else {
+            vec![]
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use catalog_io::*;
-use gstd::{prelude::*, BTreeMap};
+use catalog_state::WASM_BINARY;
+use gstd::{prelude::*, ActorId, BTreeMap};
 use gtest::{Program, System};
+use types::primitives::{PartId, TokenId};
 pub const ADMIN: u64 = 10;
 
 pub fn init_catalog(sys: &System, admin: u64) {
@@ -119,7 +121,13 @@ fn add_parts() {
 
     // Remove parts
     let removed_parts = vec![fixed_part_id_1, slot_part_id];
-    let result = catalog.send(ADMIN, CatalogAction::RemoveParts(removed_parts.clone()));
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: removed_parts.clone(),
+            force: false,
+        },
+    );
     let expected_reply: Result<CatalogReply, CatalogError> =
         Ok(CatalogReply::PartsRemoved(removed_parts));
     assert!(result.contains(&(ADMIN, expected_reply.encode())));
@@ -140,12 +148,24 @@ fn add_parts() {
     assert!(!slot_part_in_state);
 
     // Zero length array of parts
-    let result = catalog.send(ADMIN, CatalogAction::RemoveParts(vec![]));
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: vec![],
+            force: false,
+        },
+    );
     let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::ZeroLengthPassed);
     assert!(result.contains(&(ADMIN, expected_reply.encode())));
 
     // Cannot remove non-existing part
-    let result = catalog.send(ADMIN, CatalogAction::RemoveParts(vec![fixed_part_id_1]));
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: vec![fixed_part_id_1],
+            force: false,
+        },
+    );
     let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartDoesNotExist);
     assert!(result.contains(&(ADMIN, expected_reply.encode())));
 }
@@ -184,11 +204,23 @@ fn add_parts_error_cases() {
     assert!(result.contains(&(ADMIN, expected_reply.encode())));
 
     // Cannot add part with already existing id
-    let added_part = BTreeMap::from([(part_id, fixed_part_data)]);
+    let added_part = BTreeMap::from([(
+        part_id,
+        Part::Fixed(FixedPart {
+            z: Some(1),
+            metadata_uri: String::from("new_src"),
+        }),
+    )]);
     let result = catalog.send(ADMIN, CatalogAction::AddParts(added_part));
     let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartAlreadyExists);
     assert!(result.contains(&(ADMIN, expected_reply.encode())));
 
+    // the existing part is not overwritten
+    let result = catalog.send(ADMIN, CatalogAction::CheckPart(part_id));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::Part(fixed_part_data));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
     // Zero length BTreeMap
     let result = catalog.send(ADMIN, CatalogAction::AddParts(BTreeMap::new()));
     let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::ZeroLengthPassed);
@@ -237,6 +269,7 @@ fn equippable() {
         CatalogAction::CheckEquippable {
             part_id: slot_part_id_2,
             collection_id: 100.into(),
+            token_id: None,
         },
     );
     let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::NotInEquippableList);
@@ -248,6 +281,7 @@ fn equippable() {
         CatalogAction::CheckEquippable {
             part_id: slot_part_id_1,
             collection_id: 100.into(),
+            token_id: None,
         },
     );
     let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::InEquippableList);
@@ -271,6 +305,7 @@ fn equippable() {
         CatalogAction::CheckEquippable {
             part_id: slot_part_id_2,
             collection_id: 100.into(),
+            token_id: None,
         },
     );
     let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::InEquippableList);
@@ -291,6 +326,7 @@ fn equippable() {
         CatalogAction::CheckEquippable {
             part_id: slot_part_id_1,
             collection_id: 200.into(),
+            token_id: None,
         },
     );
     let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::InEquippableList);
@@ -313,6 +349,7 @@ fn equippable() {
         CatalogAction::CheckEquippable {
             part_id: slot_part_id_1,
             collection_id: 200.into(),
+            token_id: None,
         },
     );
     let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::NotInEquippableList);
@@ -334,6 +371,7 @@ fn equippable() {
         CatalogAction::CheckEquippable {
             part_id: slot_part_id_2,
             collection_id: 100.into(),
+            token_id: None,
         },
     );
     let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::NotInEquippableList);
@@ -390,3 +428,1106 @@ fn equippable() {
     let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
     assert!(result.contains(&(ADMIN, expected_reply.encode())));
 }
+
+#[test]
+fn equippable_to_all() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+
+    let slot_part_id = 1;
+    let slot_part_data = Part::Slot(SlotPart {
+        equippable: vec![],
+        z: Some(0),
+        metadata_uri: String::from("src"),
+    });
+    let parts = BTreeMap::from([(slot_part_id, slot_part_data)]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Setting the part equippable to all twice does not duplicate it
+    for _ in 0..2 {
+        let result = catalog.send(
+            ADMIN,
+            CatalogAction::SetEquippableToAll {
+                part_id: slot_part_id,
+            },
+        );
+        let expected_reply: Result<CatalogReply, CatalogError> =
+            Ok(CatalogReply::EquippableToAllSet);
+        assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    }
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.is_equippable_to_all, vec![slot_part_id]);
+
+    // Unset equippable to all
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::UnsetEquippableToAll {
+            part_id: slot_part_id,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::EquippableToAllUnset);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::CheckEquippable {
+            part_id: slot_part_id,
+            collection_id: 100.into(),
+            token_id: None,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::NotInEquippableList);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.is_equippable_to_all, vec![]);
+
+    // Cannot unset equippable to all for non existing part
+    let result = catalog.send(ADMIN, CatalogAction::UnsetEquippableToAll { part_id: 1000 });
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartDoesNotExist);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn unauthorized_calls() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+    let user = 11;
+
+    let slot_part_id = 1;
+    let slot_part_data = Part::Slot(SlotPart {
+        equippable: vec![],
+        z: Some(0),
+        metadata_uri: String::from("src"),
+    });
+    let parts = BTreeMap::from([(slot_part_id, slot_part_data)]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::PartsAdded(parts.clone()));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let actions = vec![
+        CatalogAction::AddParts(parts),
+        CatalogAction::RemoveParts {
+            part_ids: vec![slot_part_id],
+            force: false,
+        },
+        CatalogAction::AddEquippableAddresses {
+            part_id: slot_part_id,
+            collection_ids: vec![100.into()],
+        },
+        CatalogAction::RemoveEquippable {
+            part_id: slot_part_id,
+            collection_id: 100.into(),
+        },
+        CatalogAction::SetEquippableToAll {
+            part_id: slot_part_id,
+        },
+        CatalogAction::UnsetEquippableToAll {
+            part_id: slot_part_id,
+        },
+        CatalogAction::ResetEquippableAddress {
+            part_id: slot_part_id,
+        },
+    ];
+
+    for action in actions {
+        let result = catalog.send(user, action);
+        let expected_reply: Result<CatalogReply, CatalogError> =
+            Err(CatalogError::NotAllowedToCall);
+        assert!(result.contains(&(user, expected_reply.encode())));
+    }
+
+    // The part is still equippable only by the collections from its list
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.is_equippable_to_all, vec![]);
+    assert_eq!(state.parts.len(), 1);
+}
+
+#[test]
+fn update_part() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+
+    let fixed_part_id = 1;
+    let slot_part_id = 2;
+    let mut parts = BTreeMap::new();
+    parts.insert(
+        fixed_part_id,
+        Part::Fixed(FixedPart {
+            z: Some(0),
+            metadata_uri: String::from("scr"),
+        }),
+    );
+    parts.insert(
+        slot_part_id,
+        Part::Slot(SlotPart {
+            equippable: vec![100.into()],
+            z: Some(1),
+            metadata_uri: String::from("slot"),
+        }),
+    );
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Fix the metadata URI of the fixed part
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::UpdatePart {
+            part_id: fixed_part_id,
+            metadata_uri: String::from("src"),
+            z: Some(0),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartUpdated {
+        part_id: fixed_part_id,
+        revision: 1,
+    });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Re-layer the slot part, its equippable list is preserved
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::UpdatePart {
+            part_id: slot_part_id,
+            metadata_uri: String::from("slot"),
+            z: Some(5),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartUpdated {
+        part_id: slot_part_id,
+        revision: 1,
+    });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::UpdatePart {
+            part_id: slot_part_id,
+            metadata_uri: String::from("slot_v2"),
+            z: Some(5),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartUpdated {
+        part_id: slot_part_id,
+        revision: 2,
+    });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert!(state.parts.contains(&(
+        fixed_part_id,
+        Part::Fixed(FixedPart {
+            z: Some(0),
+            metadata_uri: String::from("src"),
+        })
+    )));
+    assert!(state.parts.contains(&(
+        slot_part_id,
+        Part::Slot(SlotPart {
+            equippable: vec![100.into()],
+            z: Some(5),
+            metadata_uri: String::from("slot_v2"),
+        })
+    )));
+    let mut part_revisions = state.part_revisions;
+    part_revisions.sort();
+    assert_eq!(part_revisions, vec![(fixed_part_id, 1), (slot_part_id, 2)]);
+
+    // Cannot update non existing part
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::UpdatePart {
+            part_id: 100,
+            metadata_uri: String::from("src"),
+            z: None,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartDoesNotExist);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Only admin can update parts
+    let result = catalog.send(
+        11,
+        CatalogAction::UpdatePart {
+            part_id: fixed_part_id,
+            metadata_uri: String::from("src"),
+            z: None,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(11, expected_reply.encode())));
+}
+
+#[test]
+fn lock_catalog() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+
+    let fixed_part_id = 1;
+    let slot_part_id_1 = 2;
+    let slot_part_id_2 = 3;
+    let fixed_part_data = Part::Fixed(FixedPart {
+        z: Some(0),
+        metadata_uri: String::from("src"),
+    });
+    let slot_part_data = Part::Slot(SlotPart {
+        equippable: vec![],
+        z: Some(0),
+        metadata_uri: String::from("src"),
+    });
+    let mut parts = BTreeMap::new();
+    parts.insert(fixed_part_id, fixed_part_data.clone());
+    parts.insert(slot_part_id_1, slot_part_data.clone());
+    parts.insert(slot_part_id_2, slot_part_data);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Lock the equippable list of the first slot part
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::LockEquippableList {
+            part_id: slot_part_id_1,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::EquippableListLocked(slot_part_id_1));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Cannot lock the equippable list of the fixed part
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::LockEquippableList {
+            part_id: fixed_part_id,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // The equippable list of the locked part cannot be changed and the part cannot be removed
+    let actions = vec![
+        CatalogAction::AddEquippableAddresses {
+            part_id: slot_part_id_1,
+            collection_ids: vec![100.into()],
+        },
+        CatalogAction::RemoveEquippable {
+            part_id: slot_part_id_1,
+            collection_id: 100.into(),
+        },
+        CatalogAction::SetEquippableToAll {
+            part_id: slot_part_id_1,
+        },
+        CatalogAction::ResetEquippableAddress {
+            part_id: slot_part_id_1,
+        },
+        CatalogAction::RemoveParts {
+            part_ids: vec![slot_part_id_1],
+            force: false,
+        },
+    ];
+    for action in actions {
+        let result = catalog.send(ADMIN, action);
+        let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::CatalogLocked);
+        assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    }
+
+    // The equippable list of the other slot part is still editable
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::AddEquippableAddresses {
+            part_id: slot_part_id_2,
+            collection_ids: vec![100.into()],
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::EquippablesAdded {
+        part_id: slot_part_id_2,
+        collection_ids: vec![100.into()],
+    });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Lock the whole catalog
+    let result = catalog.send(ADMIN, CatalogAction::LockCatalog);
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::CatalogLocked);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert!(state.is_locked);
+    assert_eq!(state.locked_equippable_parts, vec![slot_part_id_1]);
+
+    let actions = vec![
+        CatalogAction::AddParts(BTreeMap::from([(10, fixed_part_data)])),
+        CatalogAction::RemoveParts {
+            part_ids: vec![fixed_part_id],
+            force: false,
+        },
+        CatalogAction::UpdatePart {
+            part_id: fixed_part_id,
+            metadata_uri: String::from("src2"),
+            z: None,
+        },
+        CatalogAction::AddEquippableAddresses {
+            part_id: slot_part_id_2,
+            collection_ids: vec![200.into()],
+        },
+        CatalogAction::UnsetEquippableToAll {
+            part_id: slot_part_id_2,
+        },
+    ];
+    for action in actions {
+        let result = catalog.send(ADMIN, action);
+        let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::CatalogLocked);
+        assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    }
+
+    // Locking the locked catalog is idempotent
+    let result = catalog.send(ADMIN, CatalogAction::LockCatalog);
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::CatalogLocked);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert!(state.is_locked);
+
+    // Checks are still available
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::CheckEquippable {
+            part_id: slot_part_id_2,
+            collection_id: 100.into(),
+            token_id: None,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::InEquippableList);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn equippable_tokens() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+
+    let slot_part_id = 1;
+    let fixed_part_id = 2;
+    let slot_part_data = Part::Slot(SlotPart {
+        equippable: vec![],
+        z: Some(0),
+        metadata_uri: String::from("src"),
+    });
+    let fixed_part_data = Part::Fixed(FixedPart {
+        z: Some(0),
+        metadata_uri: String::from("src"),
+    });
+    let parts = BTreeMap::from([
+        (slot_part_id, slot_part_data),
+        (fixed_part_id, fixed_part_data),
+    ]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Allow only the token 5 from the collection 100
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::SetEquippableList {
+            part_id: slot_part_id,
+            list: EquippableList::Custom(BTreeSet::from([(100.into(), 5.into())])),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::EquippableListSet(slot_part_id));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(
+        state.equippable_tokens,
+        vec![(slot_part_id, vec![(100.into(), 5.into())])]
+    );
+
+    for (token_id, expected_reply) in [
+        (Some(5.into()), CatalogReply::InEquippableList),
+        (Some(6.into()), CatalogReply::NotInEquippableList),
+        (None, CatalogReply::NotInEquippableList),
+    ] {
+        let result = catalog.send(
+            ADMIN,
+            CatalogAction::CheckEquippable {
+                part_id: slot_part_id,
+                collection_id: 100.into(),
+                token_id,
+            },
+        );
+        let expected_reply: Result<CatalogReply, CatalogError> = Ok(expected_reply);
+        assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    }
+
+    // The token list can't be set for the fixed part
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::SetEquippableList {
+            part_id: fixed_part_id,
+            list: EquippableList::All,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Resetting the equippable addresses clears the token list
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::ResetEquippableAddress {
+            part_id: slot_part_id,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::EqippableAddressesReset);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert!(state.equippable_tokens.is_empty());
+
+    let set_list = |list: EquippableList| {
+        let result = catalog.send(
+            ADMIN,
+            CatalogAction::SetEquippableList {
+                part_id: slot_part_id,
+                list,
+            },
+        );
+        let expected_reply: Result<CatalogReply, CatalogError> =
+            Ok(CatalogReply::EquippableListSet(slot_part_id));
+        assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    };
+
+    // `EquippableList::All` makes the part equippable to all and clears the token list
+    set_list(EquippableList::Custom(BTreeSet::from([(
+        100.into(),
+        5.into(),
+    )])));
+    set_list(EquippableList::All);
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.is_equippable_to_all, vec![slot_part_id]);
+    assert!(state.equippable_tokens.is_empty());
+
+    // `EquippableList::Custom` restricts the part to the listed tokens again
+    set_list(EquippableList::Custom(BTreeSet::from([(
+        100.into(),
+        5.into(),
+    )])));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert!(state.is_equippable_to_all.is_empty());
+    assert_eq!(
+        state.equippable_tokens,
+        vec![(slot_part_id, vec![(100.into(), 5.into())])]
+    );
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::CheckEquippable {
+            part_id: slot_part_id,
+            collection_id: 100.into(),
+            token_id: Some(6.into()),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::NotInEquippableList);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn admin_transfer() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+    let new_admin = 11;
+    let user = 12;
+
+    let result = catalog.send(
+        user,
+        CatalogAction::TransferAdmin {
+            new_admin: user.into(),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(user, expected_reply.encode())));
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::TransferAdmin {
+            new_admin: new_admin.into(),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::AdminTransferProposed(new_admin.into()));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.admin, ADMIN.into());
+    assert_eq!(state.pending_admin, Some(new_admin.into()));
+
+    // Only the pending admin can accept the transfer
+    let result = catalog.send(user, CatalogAction::AcceptAdmin);
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(user, expected_reply.encode())));
+
+    let result = catalog.send(new_admin, CatalogAction::AcceptAdmin);
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::AdminTransferred(new_admin.into()));
+    assert!(result.contains(&(new_admin, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.admin, new_admin.into());
+    assert_eq!(state.pending_admin, None);
+
+    // The previous admin loses the access
+    let result = catalog.send(ADMIN, CatalogAction::LockCatalog);
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(new_admin, CatalogAction::LockCatalog);
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::CatalogLocked);
+    assert!(result.contains(&(new_admin, expected_reply.encode())));
+}
+
+#[test]
+fn roles() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+    let part_manager = 11;
+    let equippable_manager = 12;
+
+    for (role, account) in [
+        (CatalogRole::PartManager, part_manager),
+        (CatalogRole::EquippableManager, equippable_manager),
+    ] {
+        // Only admin can grant roles
+        let result = catalog.send(
+            account,
+            CatalogAction::GrantRole {
+                role,
+                account: account.into(),
+            },
+        );
+        let expected_reply: Result<CatalogReply, CatalogError> =
+            Err(CatalogError::NotAllowedToCall);
+        assert!(result.contains(&(account, expected_reply.encode())));
+
+        let result = catalog.send(
+            ADMIN,
+            CatalogAction::GrantRole {
+                role,
+                account: account.into(),
+            },
+        );
+        let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::RoleGranted {
+            role,
+            account: account.into(),
+        });
+        assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    }
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.part_managers, vec![part_manager.into()]);
+    assert_eq!(state.equippable_managers, vec![equippable_manager.into()]);
+
+    let slot_part_id = 1;
+    let parts = BTreeMap::from([(
+        slot_part_id,
+        Part::Slot(SlotPart {
+            equippable: vec![],
+            z: Some(0),
+            metadata_uri: String::from("src"),
+        }),
+    )]);
+
+    // Equippable manager can't add parts
+    let result = catalog.send(equippable_manager, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(equippable_manager, expected_reply.encode())));
+
+    let result = catalog.send(part_manager, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(part_manager, expected_reply.encode())));
+
+    // Part manager can't change equippable lists
+    let result = catalog.send(
+        part_manager,
+        CatalogAction::SetEquippableToAll {
+            part_id: slot_part_id,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(part_manager, expected_reply.encode())));
+
+    let result = catalog.send(
+        equippable_manager,
+        CatalogAction::SetEquippableToAll {
+            part_id: slot_part_id,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::EquippableToAllSet);
+    assert!(result.contains(&(equippable_manager, expected_reply.encode())));
+
+    // Managers can't lock the catalog
+    let result = catalog.send(part_manager, CatalogAction::LockCatalog);
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(part_manager, expected_reply.encode())));
+
+    // Only admin can lock equippable lists
+    let result = catalog.send(
+        equippable_manager,
+        CatalogAction::LockEquippableList {
+            part_id: slot_part_id,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(equippable_manager, expected_reply.encode())));
+
+    // Revoked manager loses the access
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RevokeRole {
+            role: CatalogRole::PartManager,
+            account: part_manager.into(),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::RoleRevoked {
+        role: CatalogRole::PartManager,
+        account: part_manager.into(),
+    });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(
+        part_manager,
+        CatalogAction::RemoveParts {
+            part_ids: vec![slot_part_id],
+            force: false,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::NotAllowedToCall);
+    assert!(result.contains(&(part_manager, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert!(state.part_managers.is_empty());
+}
+
+#[test]
+fn state_queries() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+
+    let fixed_part = FixedPart {
+        z: Some(0),
+        metadata_uri: String::from("fixed"),
+    };
+    let slot_part = SlotPart {
+        equippable: vec![100.into()],
+        z: Some(1),
+        metadata_uri: String::from("slot"),
+    };
+    let mut parts = BTreeMap::new();
+    for part_id in 1..=3 {
+        parts.insert(part_id, Part::Fixed(fixed_part.clone()));
+    }
+    for part_id in 4..=5 {
+        parts.insert(part_id, Part::Slot(slot_part.clone()));
+    }
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let part: Option<Part> = catalog
+        .read_state_using_wasm("part", WASM_BINARY.into(), Some(4 as PartId))
+        .expect("Failed to read state");
+    assert_eq!(part, Some(Part::Slot(slot_part.clone())));
+
+    let part: Option<Part> = catalog
+        .read_state_using_wasm("part", WASM_BINARY.into(), Some(10 as PartId))
+        .expect("Failed to read state");
+    assert_eq!(part, None);
+
+    let page: Vec<(PartId, Part)> = catalog
+        .read_state_using_wasm("parts", WASM_BINARY.into(), Some((2_u32, 2_u32)))
+        .expect("Failed to read state");
+    assert_eq!(
+        page,
+        vec![
+            (3, Part::Fixed(fixed_part.clone())),
+            (4, Part::Slot(slot_part.clone())),
+        ]
+    );
+
+    let slot_parts: Vec<(PartId, SlotPart)> = catalog
+        .read_state_using_wasm::<(), _>("slot_parts", WASM_BINARY.into(), None)
+        .expect("Failed to read state");
+    assert_eq!(slot_parts, vec![(4, slot_part.clone()), (5, slot_part)]);
+
+    let fixed_parts: Vec<(PartId, FixedPart)> = catalog
+        .read_state_using_wasm::<(), _>("fixed_parts", WASM_BINARY.into(), None)
+        .expect("Failed to read state");
+    assert_eq!(fixed_parts.len(), 3);
+
+    // the token 7 from the collection 200 is allowed into the part 5
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::SetEquippableList {
+            part_id: 5,
+            list: EquippableList::Custom(BTreeSet::from([(200.into(), 7.into())])),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::EquippableListSet(5));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let checks: [(PartId, u64, Option<TokenId>, bool); 6] = [
+        (4, 100, None, true),
+        (4, 200, None, false),
+        (1, 100, None, false),
+        (5, 200, Some(7.into()), true),
+        (5, 200, Some(8.into()), false),
+        (5, 200, None, false),
+    ];
+    for (part_id, collection_id, token_id, expected) in checks {
+        let is_equippable: bool = catalog
+            .read_state_using_wasm(
+                "is_equippable",
+                WASM_BINARY.into(),
+                Some((part_id, ActorId::from(collection_id), token_id)),
+            )
+            .expect("Failed to read state");
+        assert_eq!(is_equippable, expected);
+    }
+
+    let collections: Vec<ActorId> = catalog
+        .read_state_using_wasm(
+            "equippable_collections",
+            WASM_BINARY.into(),
+            Some(5 as PartId),
+        )
+        .expect("Failed to read state");
+    assert_eq!(collections, vec![100.into()]);
+}
+
+#[test]
+fn batch_checks() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+
+    let fixed_part = Part::Fixed(FixedPart {
+        z: Some(0),
+        metadata_uri: String::from("fixed"),
+    });
+    let slot_part = Part::Slot(SlotPart {
+        equippable: vec![100.into()],
+        z: Some(1),
+        metadata_uri: String::from("slot"),
+    });
+    let parts = BTreeMap::from([(1, fixed_part.clone()), (2, slot_part.clone())]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(ADMIN, CatalogAction::CheckParts(vec![2, 1]));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::Parts(vec![slot_part, fixed_part]));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(ADMIN, CatalogAction::CheckParts(vec![1, 3]));
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartDoesNotExist);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // The token 7 from the collection 200 is allowed into the slot part
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::SetEquippableList {
+            part_id: 2,
+            list: EquippableList::Custom(BTreeSet::from([(200.into(), 7.into())])),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::EquippableListSet(2));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::CheckEquippableBatch(vec![
+            (2, 100.into(), None),
+            (2, 200.into(), None),
+            (2, 200.into(), Some(7.into())),
+            (2, 200.into(), Some(8.into())),
+        ]),
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::EquippableBatch(vec![
+            true, false, true, false,
+        ]));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // The batch fails if any of the parts is not a slot part
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::CheckEquippableBatch(vec![(2, 100.into(), None), (1, 100.into(), None)]),
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn remove_parts_with_dependents() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+    let collection: u64 = 100;
+    let other_collection: u64 = 101;
+
+    let fixed_part = Part::Fixed(FixedPart {
+        z: Some(0),
+        metadata_uri: String::from("fixed"),
+    });
+    let parts = BTreeMap::from([(1, fixed_part.clone()), (2, fixed_part)]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Can't depend on non-existing part
+    let result = catalog.send(collection, CatalogAction::RegisterDependent(vec![1, 3]));
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartDoesNotExist);
+    assert!(result.contains(&(collection, expected_reply.encode())));
+
+    let result = catalog.send(collection, CatalogAction::RegisterDependent(vec![1]));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::DependentRegistered(vec![1]));
+    assert!(result.contains(&(collection, expected_reply.encode())));
+
+    // Any account can register as a dependent
+    let result = catalog.send(other_collection, CatalogAction::RegisterDependent(vec![2]));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::DependentRegistered(vec![2]));
+    assert!(result.contains(&(other_collection, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    let mut dependents = state.dependents;
+    dependents.sort();
+    assert_eq!(
+        dependents,
+        vec![
+            (1, vec![collection.into()]),
+            (2, vec![other_collection.into()])
+        ]
+    );
+
+    // Only the dependents approved by the admin keep the parts
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::GrantRole {
+            role: CatalogRole::DependentCollection,
+            account: collection.into(),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::RoleGranted {
+        role: CatalogRole::DependentCollection,
+        account: collection.into(),
+    });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // The part with dependents is not removed
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: vec![1, 2],
+            force: false,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(
+        CatalogError::PartsHaveDependents(vec![(1, vec![collection.into()])]),
+    );
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert_eq!(state.parts.len(), 2);
+
+    // Another account can't unregister the collection
+    let result = catalog.send(ADMIN, CatalogAction::UnregisterDependent(vec![1]));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::DependentUnregistered(vec![1]));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    let mut dependents = state.dependents;
+    dependents.sort();
+    assert_eq!(
+        dependents,
+        vec![
+            (1, vec![collection.into()]),
+            (2, vec![other_collection.into()])
+        ]
+    );
+
+    // The part can be removed after the collection no longer depends on it
+    let result = catalog.send(collection, CatalogAction::UnregisterDependent(vec![1]));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::DependentUnregistered(vec![1]));
+    assert!(result.contains(&(collection, expected_reply.encode())));
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: vec![1],
+            force: false,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::PartsRemoved(vec![1]));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Force flag overrides the check
+    let result = catalog.send(collection, CatalogAction::RegisterDependent(vec![2]));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::DependentRegistered(vec![2]));
+    assert!(result.contains(&(collection, expected_reply.encode())));
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: vec![2],
+            force: true,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::PartsRemoved(vec![2]));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let state: CatalogState = catalog.read_state().expect("Failed to decode the state");
+    assert!(state.parts.is_empty());
+    assert!(state.dependents.is_empty());
+}
+
+#[test]
+fn typed_parts() {
+    let system = System::new();
+    init_catalog(&system, ADMIN);
+    let catalog = system.get_program(1);
+
+    // The encoding of the existing parts is preserved
+    let fixed_part = Part::Fixed(FixedPart {
+        z: Some(0),
+        metadata_uri: String::from("fixed"),
+    });
+    assert_eq!(fixed_part.encode()[0], 0);
+
+    let text_part = Part::Text(TextPart {
+        z: Some(5),
+        text: String::from("Kanaria"),
+        font: String::from("Roboto"),
+        font_size: 12,
+        color: String::from("#FF00aa"),
+    });
+    let palette_part = Part::Palette(PalettePart {
+        z: None,
+        colors: vec![String::from("#000000"), String::from("#ffffff80")],
+    });
+    let animation_part = Part::Animation(AnimationPart {
+        z: Some(1),
+        metadata_uri: String::from("ipfs://animation"),
+        frame_count: 24,
+        frame_duration: 40,
+        looped: true,
+    });
+    let parts = BTreeMap::from([
+        (1, text_part.clone()),
+        (2, palette_part),
+        (3, animation_part),
+    ]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts.clone()));
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsAdded(parts));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(ADMIN, CatalogAction::CheckPart(1));
+    let expected_reply: Result<CatalogReply, CatalogError> =
+        Ok(CatalogReply::Part(text_part.clone()));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Only slot parts can be equippable
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::CheckEquippable {
+            part_id: 1,
+            collection_id: 100.into(),
+            token_id: None,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(ADMIN, CatalogAction::SetEquippableToAll { part_id: 3 });
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // The animation part can be updated, while the text part has no metadata URI
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::UpdatePart {
+            part_id: 3,
+            metadata_uri: String::from("ipfs://animation/2"),
+            z: Some(2),
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartUpdated {
+        part_id: 3,
+        revision: 1,
+    });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::UpdatePart {
+            part_id: 1,
+            metadata_uri: String::from("ipfs://text"),
+            z: None,
+        },
+    );
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // Colours must be in hex format
+    let parts = BTreeMap::from([(
+        4,
+        Part::Palette(PalettePart {
+            z: None,
+            colors: vec![String::from("red")],
+        }),
+    )]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts));
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // The batch with an invalid part is not added partially
+    let parts = BTreeMap::from([
+        (5, text_part),
+        (
+            6,
+            Part::Animation(AnimationPart {
+                z: Some(1),
+                metadata_uri: String::from("ipfs://animation"),
+                frame_count: 0,
+                frame_duration: 40,
+                looped: false,
+            }),
+        ),
+    ]);
+    let result = catalog.send(ADMIN, CatalogAction::AddParts(parts));
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = catalog.send(ADMIN, CatalogAction::CheckPart(5));
+    let expected_reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartDoesNotExist);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
@@ -2,13 +2,16 @@
 
 use gmeta::{In, InOut, Metadata};
 use gstd::{prelude::*, ActorId};
-use types::primitives::{BaseId, PartId, ResourceId};
+use parity_scale_codec::{DecodeAll, Error};
+use types::primitives::{
+    BaseId, CollectionAndToken, CollectionId, LegacyResourceId, PartId, ResourceId, TokenId,
+};
 
 pub struct ResourceMetadata;
 
 impl Metadata for ResourceMetadata {
     type Init = In<InitResource>;
-    type Handle = InOut<ResourceAction, ResourceEvent>;
+    type Handle = InOut<ResourceAction, Result<ResourceEvent, ResourceError>>;
     type Others = ();
     type Reply = ();
     type Signal = ();
@@ -18,12 +21,148 @@ impl Metadata for ResourceMetadata {
 #[derive(Debug, Default, Encode, Decode, TypeInfo)]
 pub struct ResourceState {
     pub name: String,
-    // the admin is the rmrk contract that initializes the storage contract
+    // the admin is the rmrk contract or the account that initializes the storage contract
     pub admin: ActorId,
+    // the account that manages the storage together with the admin
+    pub owner: Option<ActorId>,
     pub resources: Vec<(ResourceId, Resource)>,
+    // the tokens of the RMRK contracts that reference the resource
+    pub token_references: Vec<(ResourceId, Vec<CollectionAndToken>)>,
+    // the hashes of the content behind the metadata URIs of the resources
+    pub metadata_hashes: Vec<(ResourceId, [u8; 32])>,
+    // the RMRK contracts that are allowed to reference the resources besides the admin
+    pub collections: Vec<CollectionId>,
 }
 
-#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+impl ResourceState {
+    /// Decodes the state encoded either in the current format
+    /// or in the format with `u8` resource identifiers and without media descriptors.
+    pub fn decode_any(mut bytes: &[u8]) -> Result<Self, Error> {
+        let mut legacy_bytes = bytes;
+        ResourceState::decode_all(&mut bytes).or_else(|error| {
+            LegacyResourceState::decode_all(&mut legacy_bytes)
+                .map(Into::into)
+                .map_err(|_| error)
+        })
+    }
+}
+
+/// The state of the resource storage contract with `u8` resource identifiers.
+#[derive(Debug, Default, Encode, Decode, TypeInfo)]
+pub struct LegacyResourceState {
+    pub name: String,
+    pub admin: ActorId,
+    pub resources: Vec<(LegacyResourceId, LegacyResource)>,
+}
+
+/// The resource encoded without the media descriptor.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub enum LegacyResource {
+    Basic {
+        src: String,
+        thumb: Option<String>,
+        metadata_uri: String,
+    },
+    Slot {
+        src: String,
+        thumb: String,
+        metadata_uri: String,
+        base: BaseId,
+        slot: PartId,
+    },
+    Composed {
+        src: String,
+        thumb: String,
+        metadata_uri: String,
+        base: BaseId,
+        parts: Vec<PartId>,
+    },
+}
+
+impl From<LegacyResource> for Resource {
+    fn from(resource: LegacyResource) -> Self {
+        match resource {
+            LegacyResource::Basic {
+                src,
+                thumb,
+                metadata_uri,
+            } => Resource::Basic(BasicResource {
+                src,
+                thumb,
+                metadata_uri,
+                media: None,
+            }),
+            LegacyResource::Slot {
+                src,
+                thumb,
+                metadata_uri,
+                base,
+                slot,
+            } => Resource::Slot(SlotResource {
+                src,
+                thumb,
+                metadata_uri,
+                base,
+                slot,
+                media: None,
+            }),
+            LegacyResource::Composed {
+                src,
+                thumb,
+                metadata_uri,
+                base,
+                parts,
+            } => Resource::Composed(ComposedResource {
+                src,
+                thumb,
+                metadata_uri,
+                base,
+                parts,
+                media: None,
+            }),
+        }
+    }
+}
+
+impl From<LegacyResourceState> for ResourceState {
+    fn from(state: LegacyResourceState) -> Self {
+        ResourceState {
+            name: state.name,
+            admin: state.admin,
+            owner: None,
+            resources: state
+                .resources
+                .into_iter()
+                .map(|(id, resource)| (id.into(), resource.into()))
+                .collect(),
+            token_references: Vec::new(),
+            metadata_hashes: Vec::new(),
+            collections: Vec::new(),
+        }
+    }
+}
+
+/// Describes the media of the resource, so players can pick the right renderer
+/// and verify the downloaded content.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct MediaDescriptor {
+    /// MIME type of the media, e.g. `image/svg+xml`
+    pub mime_type: String,
+
+    /// Width of the media in pixels
+    pub width: Option<u32>,
+
+    /// Height of the media in pixels
+    pub height: Option<u32>,
+
+    /// SHA-256 hash of the media content
+    pub content_hash: Option<[u8; 32]>,
+
+    /// License of the media, e.g. SPDX identifier or URI
+    pub license: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub struct BasicResource {
     /// URI like IPFS hash
     pub src: String,
@@ -34,9 +173,12 @@ pub struct BasicResource {
 
     /// Reference to IPFS location of metadata
     pub metadata_uri: String,
+
+    /// Optional description of the resource media
+    pub media: Option<MediaDescriptor>,
 }
 
-#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub struct ComposedResource {
     /// URI like ipfs hash
     pub src: String,
@@ -53,9 +195,12 @@ pub struct ComposedResource {
 
     //  If a resource is composed, it will have an array of parts that compose it
     pub parts: Vec<PartId>,
+
+    /// Optional description of the resource media
+    pub media: Option<MediaDescriptor>,
 }
 
-#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub struct SlotResource {
     /// URI like ipfs hash
     pub src: String,
@@ -72,17 +217,66 @@ pub struct SlotResource {
 
     /// If the resource has the slot property, it was designed to fit into a specific Base's slot.
     pub slot: PartId,
+
+    /// Optional description of the resource media
+    pub media: Option<MediaDescriptor>,
 }
-#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub enum Resource {
     Basic(BasicResource),
     Slot(SlotResource),
     Composed(ComposedResource),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum ResourceKind {
+    Basic,
+    Slot,
+    Composed,
+}
+
+impl Resource {
+    pub fn kind(&self) -> ResourceKind {
+        match self {
+            Resource::Basic(_) => ResourceKind::Basic,
+            Resource::Slot(_) => ResourceKind::Slot,
+            Resource::Composed(_) => ResourceKind::Composed,
+        }
+    }
+
+    /// Returns the base contract of `Slot` and `Composed` resources.
+    pub fn base(&self) -> Option<BaseId> {
+        match self {
+            Resource::Basic(_) => None,
+            Resource::Slot(SlotResource { base, .. })
+            | Resource::Composed(ComposedResource { base, .. }) => Some(*base),
+        }
+    }
+
+    pub fn metadata_uri(&self) -> &str {
+        match self {
+            Resource::Basic(BasicResource { metadata_uri, .. })
+            | Resource::Slot(SlotResource { metadata_uri, .. })
+            | Resource::Composed(ComposedResource { metadata_uri, .. }) => metadata_uri,
+        }
+    }
+
+    pub fn media(&self) -> Option<&MediaDescriptor> {
+        match self {
+            Resource::Basic(BasicResource { media, .. })
+            | Resource::Slot(SlotResource { media, .. })
+            | Resource::Composed(ComposedResource { media, .. }) => media.as_ref(),
+        }
+    }
+}
+
 #[derive(Debug, Decode, Encode, TypeInfo)]
 pub struct InitResource {
     pub resource_name: String,
+    /// The account that manages the storage together with the initializer,
+    /// e.g. the issuer of the collection that creates the storage.
+    pub owner: Option<ActorId>,
 }
 
 #[derive(Debug, Decode, Encode, TypeInfo)]
@@ -90,26 +284,31 @@ pub enum ResourceAction {
     /// Adds resource entry on resource storage contract.
     ///
     /// # Requirements:
-    /// * The `msg::source()` must be the contract admin (RMRK contract).
+    /// * The `msg::source()` must be the contract admin or the storage owner.
     /// * `id` can not be equal to zero.
     /// * Resource with indicated `id` must not exist.
+    /// * The `slot` of `Slot` resource must be a slot part in the base contract.
+    /// * The `parts` of `Composed` resource must exist in the base contract.
+    /// * The media descriptor (if any) must have a MIME type and non-zero dimensions.
     ///
     /// # Arguments:
     /// * `resource_id`: is a resource identifier.
     /// * `resource`: is a resource struct that can be `Basic`, `Slot` or `Composed`.
+    /// * `metadata_hash`: optional 32-byte hash of the content behind `metadata_uri`.
     ///
     /// On success replies [`ResourceEvent::ResourceEntryAdded`].
     AddResourceEntry {
         resource_id: ResourceId,
         resource: Resource,
+        metadata_hash: Option<[u8; 32]>,
     },
 
     /// Adds part ids to composed resource.
     ///
     /// # Requirements:
-    /// * The `msg::source()` must be the contract admin (RMRK contract).
+    /// * The `msg::source()` must be the contract admin or the storage owner.
     /// * `part_id` must exist in the base contract.
-    /// * Resource with indicated `id` must not exist.
+    /// * Resource with indicated `id` must exist and must be composed.
     ///
     /// # Arguments:
     /// * `part_id`: the part id to be added to composed resource.
@@ -128,6 +327,118 @@ pub enum ResourceAction {
     ///
     /// On success replies [`ResourceEvent::Resource`].
     GetResource { id: ResourceId },
+
+    /// Updates the URIs of the resource.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or the storage owner.
+    /// * Resource with indicated `id` must exist.
+    /// * The resource must have a media descriptor if `content_hash` is set.
+    ///
+    /// # Arguments:
+    /// * `resource_id`: is a resource identifier.
+    /// * `src`: the new URI of the resource (left unchanged if `None`).
+    ///   The content hash of the media descriptor is dropped when the URI is changed without a new hash.
+    /// * `content_hash`: the new 32-byte hash of the media content (left unchanged if `None`).
+    /// * `thumb`: the new URI of the thumbnail (left unchanged if `None`).
+    /// * `metadata_uri`: the new URI of the metadata (left unchanged if `None`).
+    ///   The hash of the previous metadata is dropped when the URI is changed without a new hash.
+    /// * `metadata_hash`: the new 32-byte hash of the metadata content (left unchanged if `None`).
+    ///
+    /// On success replies [`ResourceEvent::ResourceUpdated`].
+    UpdateResource {
+        resource_id: ResourceId,
+        src: Option<String>,
+        content_hash: Option<[u8; 32]>,
+        thumb: Option<String>,
+        metadata_uri: Option<String>,
+        metadata_hash: Option<[u8; 32]>,
+    },
+
+    /// Removes the resource from the resource storage contract.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or the storage owner.
+    /// * Resource with indicated `id` must exist.
+    /// * Resource must not be referenced by any token.
+    ///
+    /// # Arguments:
+    /// * `resource_id`: is a resource identifier.
+    ///
+    /// On success replies [`ResourceEvent::ResourceRemoved`].
+    RemoveResource { resource_id: ResourceId },
+
+    /// Removes the part id from composed resource.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or the storage owner.
+    /// * Resource with indicated `id` must exist and must be composed.
+    /// * `part_id` must be among the parts of the resource.
+    ///
+    /// # Arguments:
+    /// * `resource_id`: the composed resource id.
+    /// * `part_id`: the part id to be removed from composed resource.
+    ///
+    /// On success replies [`ResourceEvent::PartIdRemovedFromResource`].
+    RemovePartFromResource {
+        resource_id: ResourceId,
+        part_id: PartId,
+    },
+
+    /// Records that the token of the RMRK contract (`msg::source()`) references the resource.
+    /// The referenced resource can not be removed.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or an allowed collection.
+    /// * Resource with indicated `id` must exist.
+    ///
+    /// # Arguments:
+    /// * `resource_id`: is a resource identifier.
+    /// * `token_id`: the token that references the resource.
+    ///
+    /// On success replies [`ResourceEvent::TokenReferenceAdded`].
+    AddTokenReference {
+        resource_id: ResourceId,
+        token_id: TokenId,
+    },
+
+    /// Records that the token of the RMRK contract (`msg::source()`) no longer references the resource.
+    /// Only the references of the `msg::source()` are removed, so it can also be called
+    /// by a collection that was disallowed.
+    ///
+    /// # Arguments:
+    /// * `resource_id`: is a resource identifier.
+    /// * `token_id`: the token that referenced the resource.
+    ///
+    /// On success replies [`ResourceEvent::TokenReferenceRemoved`].
+    RemoveTokenReference {
+        resource_id: ResourceId,
+        token_id: TokenId,
+    },
+
+    /// Allows the RMRK contract to reference the resources of the storage,
+    /// so the storage can be shared between several collections.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or the storage owner.
+    ///
+    /// # Arguments:
+    /// * `collection_id`: the address of the RMRK contract.
+    ///
+    /// On success replies [`ResourceEvent::CollectionAllowed`].
+    AllowCollection(CollectionId),
+
+    /// Disallows the RMRK contract to reference the resources of the storage.
+    /// The references that the collection already holds are kept.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the contract admin or the storage owner.
+    ///
+    /// # Arguments:
+    /// * `collection_id`: the address of the RMRK contract.
+    ///
+    /// On success replies [`ResourceEvent::CollectionDisallowed`].
+    DisallowCollection(CollectionId),
 }
 
 #[derive(Debug, Encode, Decode, TypeInfo)]
@@ -138,4 +449,32 @@ pub enum ResourceEvent {
     },
     PartIdAddedToResource(PartId),
     Resource(Resource),
+    ResourceUpdated(ResourceId),
+    ResourceRemoved(ResourceId),
+    PartIdRemovedFromResource(PartId),
+    TokenReferenceAdded {
+        resource_id: ResourceId,
+        token_id: TokenId,
+    },
+    TokenReferenceRemoved {
+        resource_id: ResourceId,
+        token_id: TokenId,
+    },
+    CollectionAllowed(CollectionId),
+    CollectionDisallowed(CollectionId),
+}
+
+#[derive(Debug, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub enum ResourceError {
+    NotAllowedToCall,
+    ResourceIdCantBeZero,
+    ResourceAlreadyExists,
+    ResourceDoesNotExist,
+    ResourceMustBeComposed,
+    ResourceIsReferenced,
+    PartNotInResource,
+    WrongPartFormat,
+    PartDoesNotExistInCatalog,
+    ErrorInCatalog,
+    WrongMediaDescriptor,
 }
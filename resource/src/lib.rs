@@ -2,64 +2,307 @@
 
 use catalog_io::*;
 use gstd::{msg, prelude::*, ActorId};
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use resource_io::*;
-use types::primitives::{PartId, ResourceId};
+use types::primitives::{CollectionAndToken, CollectionId, PartId, ResourceId, TokenId};
 
 #[derive(Debug, Default)]
 struct ResourceStorage {
     name: String,
-    // the admin is the rmrk contract that initializes the storage contract
+    // the admin is the rmrk contract or the account that initializes the storage contract
     admin: ActorId,
+    // the account that manages the storage together with the admin
+    owner: Option<ActorId>,
     resources: HashMap<ResourceId, Resource>,
+    // the tokens of the RMRK contracts that reference the resource
+    token_references: HashMap<ResourceId, HashSet<CollectionAndToken>>,
+    // the hashes of the content behind the metadata URIs of the resources
+    metadata_hashes: HashMap<ResourceId, [u8; 32]>,
+    // the RMRK contracts that are allowed to reference the resources besides the admin
+    collections: HashSet<CollectionId>,
 }
 
 static mut RESOURCE_STORAGE: Option<ResourceStorage> = None;
 
 impl ResourceStorage {
-    fn add_resource_entry(&mut self, resource_id: ResourceId, resource: Resource) {
-        assert!(resource_id != 0, "Write to zero");
-        assert!(msg::source() == self.admin, "Not admin");
-        assert!(
-            self.resources
-                .insert(resource_id, resource.clone())
-                .is_none(),
-            "resource already exists"
-        );
-        msg::reply(
-            ResourceEvent::ResourceEntryAdded {
-                resource_id,
-                resource,
-            },
-            0,
-        )
-        .expect("Error in reply `[ResourceEvent::ResourceEntryAdded]`");
-    }
-
-    async fn add_part_to_resource(&mut self, resource_id: ResourceId, part_id: PartId) {
-        assert!(msg::source() == self.admin, "Not admin");
+    async fn add_resource_entry(
+        &mut self,
+        resource_id: ResourceId,
+        resource: Resource,
+        metadata_hash: Option<[u8; 32]>,
+    ) -> Result<ResourceEvent, ResourceError> {
+        self.check_new_resource_id(resource_id)?;
+        if let Some(media) = resource.media() {
+            check_media(media)?;
+        }
+        match &resource {
+            Resource::Basic(_) => {}
+            Resource::Slot(SlotResource { base, slot, .. }) => {
+                // the slot must be a slot part in the base contract
+                let parts = check_parts(*base, vec![*slot]).await?;
+                if !matches!(parts.first(), Some(Part::Slot(_))) {
+                    return Err(ResourceError::WrongPartFormat);
+                }
+            }
+            Resource::Composed(ComposedResource { base, parts, .. }) => {
+                if !parts.is_empty() {
+                    check_parts(*base, parts.clone()).await?;
+                }
+            }
+        }
+
+        // the resource with the same id could be added while waiting for the catalog reply
+        self.check_new_resource_id(resource_id)?;
+        self.resources.insert(resource_id, resource.clone());
+        if let Some(metadata_hash) = metadata_hash {
+            self.metadata_hashes.insert(resource_id, metadata_hash);
+        }
+        Ok(ResourceEvent::ResourceEntryAdded {
+            resource_id,
+            resource,
+        })
+    }
+
+    async fn add_part_to_resource(
+        &mut self,
+        resource_id: ResourceId,
+        part_id: PartId,
+    ) -> Result<ResourceEvent, ResourceError> {
+        self.check_admin()?;
+        let base = match self.get_resource(resource_id)? {
+            Resource::Composed(ComposedResource { base, .. }) => *base,
+            _ => return Err(ResourceError::ResourceMustBeComposed),
+        };
+
+        // check that part exist in base contract
+        check_parts(base, vec![part_id]).await?;
+
+        // the resource may have been removed while waiting for the catalog reply
+        match self.resources.get_mut(&resource_id) {
+            Some(Resource::Composed(ComposedResource { parts, .. })) => parts.push(part_id),
+            Some(_) => return Err(ResourceError::ResourceMustBeComposed),
+            None => return Err(ResourceError::ResourceDoesNotExist),
+        }
+        Ok(ResourceEvent::PartIdAddedToResource(part_id))
+    }
+
+    fn update_resource(
+        &mut self,
+        resource_id: ResourceId,
+        new_src: Option<String>,
+        new_content_hash: Option<[u8; 32]>,
+        new_thumb: Option<String>,
+        new_metadata_uri: Option<String>,
+        new_metadata_hash: Option<[u8; 32]>,
+    ) -> Result<ResourceEvent, ResourceError> {
+        self.check_admin()?;
+        let resource = self
+            .resources
+            .get_mut(&resource_id)
+            .ok_or(ResourceError::ResourceDoesNotExist)?;
+        if let Some(media) = media_of(resource) {
+            if new_content_hash.is_some() || new_src.is_some() {
+                // the hash of the previous content does not match the new source
+                media.content_hash = new_content_hash;
+            }
+        } else if new_content_hash.is_some() {
+            return Err(ResourceError::WrongMediaDescriptor);
+        }
+        if let Some(metadata_hash) = new_metadata_hash {
+            self.metadata_hashes.insert(resource_id, metadata_hash);
+        } else if new_metadata_uri.is_some() {
+            // the hash of the previous metadata does not match the new URI
+            self.metadata_hashes.remove(&resource_id);
+        }
+        match resource {
+            Resource::Basic(BasicResource {
+                src,
+                thumb,
+                metadata_uri,
+                ..
+            }) => {
+                update_uri(src, new_src);
+                if new_thumb.is_some() {
+                    *thumb = new_thumb;
+                }
+                update_uri(metadata_uri, new_metadata_uri);
+            }
+            Resource::Slot(SlotResource {
+                src,
+                thumb,
+                metadata_uri,
+                ..
+            })
+            | Resource::Composed(ComposedResource {
+                src,
+                thumb,
+                metadata_uri,
+                ..
+            }) => {
+                update_uri(src, new_src);
+                update_uri(thumb, new_thumb);
+                update_uri(metadata_uri, new_metadata_uri);
+            }
+        }
+        Ok(ResourceEvent::ResourceUpdated(resource_id))
+    }
+
+    fn remove_resource(&mut self, resource_id: ResourceId) -> Result<ResourceEvent, ResourceError> {
+        self.check_admin()?;
+        self.get_resource(resource_id)?;
+        if self.token_references.contains_key(&resource_id) {
+            return Err(ResourceError::ResourceIsReferenced);
+        }
+        self.resources.remove(&resource_id);
+        self.metadata_hashes.remove(&resource_id);
+        Ok(ResourceEvent::ResourceRemoved(resource_id))
+    }
+
+    fn remove_part_from_resource(
+        &mut self,
+        resource_id: ResourceId,
+        part_id: PartId,
+    ) -> Result<ResourceEvent, ResourceError> {
+        self.check_admin()?;
         let resource = self
             .resources
             .get_mut(&resource_id)
-            .expect("Resource with indicated id does not exist");
-        if let Resource::Composed(ComposedResource { base, parts, .. }) = resource {
-            // check that part exist in base contract
-            msg::send_for_reply_as::<_, CatalogReply>(
-                *base,
-                CatalogAction::CheckPart(part_id),
-                0,
-                0,
-            )
-            .expect("Error in sending async message `[BaseAction::CheckPart]` to base contract")
-            .await
-            .expect("Error in async message `[BaseAction::CheckPart]`");
-            parts.push(part_id);
+            .ok_or(ResourceError::ResourceDoesNotExist)?;
+        if let Resource::Composed(ComposedResource { parts, .. }) = resource {
+            let position = parts
+                .iter()
+                .position(|id| *id == part_id)
+                .ok_or(ResourceError::PartNotInResource)?;
+            parts.remove(position);
+            Ok(ResourceEvent::PartIdRemovedFromResource(part_id))
         } else {
-            panic!("Resource must be composed");
+            Err(ResourceError::ResourceMustBeComposed)
+        }
+    }
+
+    fn add_token_reference(
+        &mut self,
+        resource_id: ResourceId,
+        token_id: TokenId,
+    ) -> Result<ResourceEvent, ResourceError> {
+        self.check_collection()?;
+        self.get_resource(resource_id)?;
+        self.token_references
+            .entry(resource_id)
+            .or_default()
+            .insert((msg::source(), token_id));
+        Ok(ResourceEvent::TokenReferenceAdded {
+            resource_id,
+            token_id,
+        })
+    }
+
+    fn remove_token_reference(
+        &mut self,
+        resource_id: ResourceId,
+        token_id: TokenId,
+    ) -> Result<ResourceEvent, ResourceError> {
+        // only the references of the source are removed, so a disallowed
+        // collection can still release the resources it held
+        if let Some(tokens) = self.token_references.get_mut(&resource_id) {
+            tokens.remove(&(msg::source(), token_id));
+            if tokens.is_empty() {
+                self.token_references.remove(&resource_id);
+            }
         }
+        Ok(ResourceEvent::TokenReferenceRemoved {
+            resource_id,
+            token_id,
+        })
+    }
+
+    fn allow_collection(
+        &mut self,
+        collection_id: CollectionId,
+    ) -> Result<ResourceEvent, ResourceError> {
+        self.check_admin()?;
+        self.collections.insert(collection_id);
+        Ok(ResourceEvent::CollectionAllowed(collection_id))
+    }
+
+    fn disallow_collection(
+        &mut self,
+        collection_id: CollectionId,
+    ) -> Result<ResourceEvent, ResourceError> {
+        self.check_admin()?;
+        self.collections.remove(&collection_id);
+        Ok(ResourceEvent::CollectionDisallowed(collection_id))
+    }
 
-        msg::reply(ResourceEvent::PartIdAddedToResource(part_id), 0)
-            .expect("Error in reply `[ResourceEvent::PartIdAddedToResource]`");
+    fn get_resource(&self, resource_id: ResourceId) -> Result<&Resource, ResourceError> {
+        self.resources
+            .get(&resource_id)
+            .ok_or(ResourceError::ResourceDoesNotExist)
+    }
+
+    fn check_new_resource_id(&self, resource_id: ResourceId) -> Result<(), ResourceError> {
+        self.check_admin()?;
+        if resource_id == 0 {
+            return Err(ResourceError::ResourceIdCantBeZero);
+        }
+        if self.resources.contains_key(&resource_id) {
+            return Err(ResourceError::ResourceAlreadyExists);
+        }
+        Ok(())
+    }
+
+    fn check_collection(&self) -> Result<(), ResourceError> {
+        let source = msg::source();
+        if source != self.admin && !self.collections.contains(&source) {
+            return Err(ResourceError::NotAllowedToCall);
+        }
+        Ok(())
+    }
+
+    fn check_admin(&self) -> Result<(), ResourceError> {
+        let source = msg::source();
+        if source != self.admin && self.owner != Some(source) {
+            return Err(ResourceError::NotAllowedToCall);
+        }
+        Ok(())
+    }
+}
+
+async fn check_parts(base: ActorId, part_ids: Vec<PartId>) -> Result<Vec<Part>, ResourceError> {
+    let reply = msg::send_for_reply_as::<_, Result<CatalogReply, CatalogError>>(
+        base,
+        CatalogAction::CheckParts(part_ids),
+        0,
+        0,
+    )
+    .expect("Error in sending async message `[CatalogAction::CheckParts]` to base contract")
+    .await
+    .map_err(|_| ResourceError::ErrorInCatalog)?;
+    match reply {
+        Ok(CatalogReply::Parts(parts)) => Ok(parts),
+        Err(CatalogError::PartDoesNotExist) => Err(ResourceError::PartDoesNotExistInCatalog),
+        _ => Err(ResourceError::ErrorInCatalog),
+    }
+}
+
+fn check_media(media: &MediaDescriptor) -> Result<(), ResourceError> {
+    if media.mime_type.is_empty() || media.width == Some(0) || media.height == Some(0) {
+        return Err(ResourceError::WrongMediaDescriptor);
+    }
+    Ok(())
+}
+
+fn update_uri(uri: &mut String, new_uri: Option<String>) {
+    if let Some(new_uri) = new_uri {
+        *uri = new_uri;
+    }
+}
+
+fn media_of(resource: &mut Resource) -> Option<&mut MediaDescriptor> {
+    match resource {
+        Resource::Basic(BasicResource { media, .. })
+        | Resource::Slot(SlotResource { media, .. })
+        | Resource::Composed(ComposedResource { media, .. }) => media.as_mut(),
     }
 }
 
@@ -69,6 +312,7 @@ unsafe extern "C" fn init() {
     let resource = ResourceStorage {
         name: config.resource_name,
         admin: msg::source(),
+        owner: config.owner,
         ..ResourceStorage::default()
     };
     RESOURCE_STORAGE = Some(resource);
@@ -78,21 +322,57 @@ unsafe extern "C" fn init() {
 async unsafe fn main() {
     let action: ResourceAction = msg::load().expect("Could not load ResourceAction");
     let storage = unsafe { RESOURCE_STORAGE.get_or_insert(Default::default()) };
-    match action {
+    let reply = match action {
         ResourceAction::AddResourceEntry {
             resource_id,
             resource,
-        } => storage.add_resource_entry(resource_id, resource),
+            metadata_hash,
+        } => {
+            storage
+                .add_resource_entry(resource_id, resource, metadata_hash)
+                .await
+        }
         ResourceAction::AddPartToResource {
             resource_id,
             part_id,
         } => storage.add_part_to_resource(resource_id, part_id).await,
-        ResourceAction::GetResource { id } => {
-            let resource = storage.resources.get(&id).expect("Resource is not found");
-            msg::reply(ResourceEvent::Resource(resource.clone()), 0)
-                .expect("Error in reply `[ResourceEvent::Resource]`");
+        ResourceAction::GetResource { id } => storage
+            .get_resource(id)
+            .map(|resource| ResourceEvent::Resource(resource.clone())),
+        ResourceAction::UpdateResource {
+            resource_id,
+            src,
+            content_hash,
+            thumb,
+            metadata_uri,
+            metadata_hash,
+        } => storage.update_resource(
+            resource_id,
+            src,
+            content_hash,
+            thumb,
+            metadata_uri,
+            metadata_hash,
+        ),
+        ResourceAction::RemoveResource { resource_id } => storage.remove_resource(resource_id),
+        ResourceAction::RemovePartFromResource {
+            resource_id,
+            part_id,
+        } => storage.remove_part_from_resource(resource_id, part_id),
+        ResourceAction::AddTokenReference {
+            resource_id,
+            token_id,
+        } => storage.add_token_reference(resource_id, token_id),
+        ResourceAction::RemoveTokenReference {
+            resource_id,
+            token_id,
+        } => storage.remove_token_reference(resource_id, token_id),
+        ResourceAction::AllowCollection(collection_id) => storage.allow_collection(collection_id),
+        ResourceAction::DisallowCollection(collection_id) => {
+            storage.disallow_collection(collection_id)
         }
-    }
+    };
+    msg::reply(reply, 0).expect("Error in sending a reply from resource storage contract");
 }
 
 #[no_mangle]
@@ -105,11 +385,23 @@ extern "C" fn state() {
     let resource_state = ResourceState {
         name: resource.name.clone(),
         admin: resource.admin,
+        owner: resource.owner,
         resources: resource
             .resources
             .iter()
             .map(|(key, value)| (*key, value.clone()))
             .collect(),
+        token_references: resource
+            .token_references
+            .iter()
+            .map(|(key, value)| (*key, value.iter().copied().collect()))
+            .collect(),
+        metadata_hashes: resource
+            .metadata_hashes
+            .iter()
+            .map(|(key, value)| (*key, *value))
+            .collect(),
+        collections: resource.collections.iter().copied().collect(),
     };
     msg::reply(resource_state, 0).expect("Failed to share state");
 }
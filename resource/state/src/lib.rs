@@ -0,0 +1,71 @@
+#![no_std]
+
+use gmeta::metawasm;
+use gstd::prelude::*;
+use resource_io::*;
+use types::primitives::{BaseId, ResourceId};
+
+#[cfg(feature = "binary-vendor")]
+include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
+
+#[metawasm]
+pub mod metafns {
+    pub type State = ResourceState;
+
+    pub fn resource(state: State, resource_id: ResourceId) -> Option<Resource> {
+        state
+            .resources
+            .into_iter()
+            .find(|(id, _)| id == &resource_id)
+            .map(|(_, resource)| resource)
+    }
+
+    /// Returns the metadata URI of the resource and the hash of its content (if any).
+    pub fn resource_metadata(
+        state: State,
+        resource_id: ResourceId,
+    ) -> Option<(String, Option<[u8; 32]>)> {
+        let metadata_hash = state
+            .metadata_hashes
+            .iter()
+            .find(|(id, _)| id == &resource_id)
+            .map(|(_, hash)| *hash);
+        state
+            .resources
+            .into_iter()
+            .find(|(id, _)| id == &resource_id)
+            .map(|(_, resource)| (resource.metadata_uri().to_string(), metadata_hash))
+    }
+
+    /// Returns at most `limit` resources sorted by their ids, starting from `offset`.
+    pub fn resources(state: State, offset: u32, limit: u32) -> Vec<(ResourceId, Resource)> {
+        let mut resources = state.resources;
+        resources.sort_by_key(|(id, _)| *id);
+        resources
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    pub fn resources_by_type(state: State, kind: ResourceKind) -> Vec<(ResourceId, Resource)> {
+        let mut resources: Vec<(ResourceId, Resource)> = state
+            .resources
+            .into_iter()
+            .filter(|(_, resource)| resource.kind() == kind)
+            .collect();
+        resources.sort_by_key(|(id, _)| *id);
+        resources
+    }
+
+    /// Returns the `Slot` and `Composed` resources that use the base contract.
+    pub fn resources_for_base(state: State, base_id: BaseId) -> Vec<(ResourceId, Resource)> {
+        let mut resources: Vec<(ResourceId, Resource)> = state
+            .resources
+            .into_iter()
+            .filter(|(_, resource)| resource.base() == Some(base_id))
+            .collect();
+        resources.sort_by_key(|(id, _)| *id);
+        resources
+    }
+}
@@ -0,0 +1,859 @@
+use catalog_io::*;
+use gstd::{prelude::*, BTreeMap};
+use gtest::{Program, System};
+use resource_io::*;
+use resource_state::WASM_BINARY;
+use types::primitives::{BaseId, ResourceId};
+pub const ADMIN: u64 = 10;
+pub const USER: u64 = 11;
+pub const COLLECTION: u64 = 12;
+pub const CATALOG_ID: u64 = 2;
+const PATH_TO_CATALOG: &str = "../target/wasm32-unknown-unknown/release/rmrk_catalog.opt.wasm";
+
+pub fn init_resource_storage(sys: &System, admin: u64) {
+    sys.init_logger();
+    let resource_storage = Program::current(sys);
+    let res = resource_storage.send(
+        admin,
+        InitResource {
+            resource_name: "ResourceName".to_string(),
+            owner: None,
+        },
+    );
+    assert!(!res.main_failed());
+}
+
+// adds fixed parts with ids 1 and 2 and slot part with id 3
+pub fn setup_catalog(sys: &System) {
+    let catalog = Program::from_file_with_id(sys, CATALOG_ID, PATH_TO_CATALOG);
+    let res = catalog.send(
+        ADMIN,
+        InitCatalog {
+            catalog_type: "svg".to_string(),
+            symbol: "CatalogSymbol".to_string(),
+        },
+    );
+    assert!(!res.main_failed());
+
+    let mut parts = BTreeMap::new();
+    for part_id in [1, 2] {
+        parts.insert(
+            part_id,
+            Part::Fixed(FixedPart {
+                z: Some(0),
+                metadata_uri: String::from("src"),
+            }),
+        );
+    }
+    parts.insert(
+        3,
+        Part::Slot(SlotPart {
+            equippable: vec![],
+            z: Some(1),
+            metadata_uri: String::from("src"),
+        }),
+    );
+    let res = catalog.send(ADMIN, CatalogAction::AddParts(parts));
+    assert!(!res.main_failed());
+}
+
+fn basic_resource() -> Resource {
+    Resource::Basic(BasicResource {
+        src: String::from("src"),
+        thumb: None,
+        metadata_uri: String::from("metadata_uri"),
+        media: None,
+    })
+}
+
+#[test]
+fn resource_errors() {
+    let system = System::new();
+    init_resource_storage(&system, ADMIN);
+    let resource_storage = system.get_program(1);
+
+    // must fail since the caller is not the admin
+    let result = resource_storage.send(
+        USER,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: basic_resource(),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::NotAllowedToCall);
+    assert!(result.contains(&(USER, expected_reply.encode())));
+
+    // must fail since the resource id is zero
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 0,
+            resource: basic_resource(),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::ResourceIdCantBeZero);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: basic_resource(),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::ResourceEntryAdded {
+            resource_id: 1,
+            resource: basic_resource(),
+        });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // must fail since the resource already exists
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: basic_resource(),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::ResourceAlreadyExists);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // must fail since the resource does not exist
+    let result = resource_storage.send(ADMIN, ResourceAction::GetResource { id: 2 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::ResourceDoesNotExist);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // must fail since the resource is not composed
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddPartToResource {
+            resource_id: 1,
+            part_id: 1,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::ResourceMustBeComposed);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn update_and_remove_resources() {
+    let system = System::new();
+    init_resource_storage(&system, ADMIN);
+    setup_catalog(&system);
+    let resource_storage = system.get_program(1);
+
+    let composed_resource = Resource::Composed(ComposedResource {
+        src: String::from("src"),
+        thumb: String::from("thumb"),
+        metadata_uri: String::from("metadata_uri"),
+        base: CATALOG_ID.into(),
+        parts: vec![1, 2],
+        media: None,
+    });
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: composed_resource,
+            metadata_hash: None,
+        },
+    );
+    assert!(!result.main_failed());
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::UpdateResource {
+            resource_id: 1,
+            src: Some(String::from("new_src")),
+            content_hash: None,
+            thumb: None,
+            metadata_uri: None,
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::ResourceUpdated(1));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(ADMIN, ResourceAction::GetResource { id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> = Ok(ResourceEvent::Resource(
+        Resource::Composed(ComposedResource {
+            src: String::from("new_src"),
+            thumb: String::from("thumb"),
+            metadata_uri: String::from("metadata_uri"),
+            base: CATALOG_ID.into(),
+            parts: vec![1, 2],
+            media: None,
+        }),
+    ));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::RemovePartFromResource {
+            resource_id: 1,
+            part_id: 1,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::PartIdRemovedFromResource(1));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // must fail since the part was already removed
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::RemovePartFromResource {
+            resource_id: 1,
+            part_id: 1,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::PartNotInResource);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddTokenReference {
+            resource_id: 1,
+            token_id: 5.into(),
+        },
+    );
+    assert!(!result.main_failed());
+
+    // must fail since the token references the resource
+    let result = resource_storage.send(ADMIN, ResourceAction::RemoveResource { resource_id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::ResourceIsReferenced);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::RemoveTokenReference {
+            resource_id: 1,
+            token_id: 5.into(),
+        },
+    );
+    assert!(!result.main_failed());
+
+    let result = resource_storage.send(ADMIN, ResourceAction::RemoveResource { resource_id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::ResourceRemoved(1));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(ADMIN, ResourceAction::GetResource { id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::ResourceDoesNotExist);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn decode_legacy_state() {
+    let legacy_state = LegacyResourceState {
+        name: "ResourceName".to_string(),
+        admin: ADMIN.into(),
+        resources: vec![(
+            255,
+            LegacyResource::Basic {
+                src: String::from("src"),
+                thumb: None,
+                metadata_uri: String::from("metadata_uri"),
+            },
+        )],
+    };
+    let state = ResourceState::decode_any(&legacy_state.encode()).expect("Unable to decode state");
+    assert_eq!(state.resources[0], (255, basic_resource()));
+    assert!(state.token_references.is_empty());
+
+    // the current format is decoded as is
+    let state = ResourceState {
+        resources: vec![(u64::MAX, basic_resource())],
+        ..ResourceState::default()
+    };
+    let state = ResourceState::decode_any(&state.encode()).expect("Unable to decode state");
+    assert_eq!(state.resources[0].0, u64::MAX);
+}
+
+#[test]
+fn validate_resources_against_catalog() {
+    let system = System::new();
+    init_resource_storage(&system, ADMIN);
+    setup_catalog(&system);
+    let resource_storage = system.get_program(1);
+
+    let slot_resource = |slot| {
+        Resource::Slot(SlotResource {
+            src: String::from("src"),
+            thumb: String::from("thumb"),
+            metadata_uri: String::from("metadata_uri"),
+            base: CATALOG_ID.into(),
+            slot,
+            media: None,
+        })
+    };
+    let composed_resource = |parts| {
+        Resource::Composed(ComposedResource {
+            src: String::from("src"),
+            thumb: String::from("thumb"),
+            metadata_uri: String::from("metadata_uri"),
+            base: CATALOG_ID.into(),
+            parts,
+            media: None,
+        })
+    };
+
+    // must fail since the slot is a fixed part
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: slot_resource(1),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::WrongPartFormat);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // must fail since the slot does not exist
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: slot_resource(4),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::PartDoesNotExistInCatalog);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: slot_resource(3),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::ResourceEntryAdded {
+            resource_id: 1,
+            resource: slot_resource(3),
+        });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // must fail since one of the parts does not exist
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 2,
+            resource: composed_resource(vec![1, 5]),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::PartDoesNotExistInCatalog);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 2,
+            resource: composed_resource(vec![1, 2]),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::ResourceEntryAdded {
+            resource_id: 2,
+            resource: composed_resource(vec![1, 2]),
+        });
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn state_queries() {
+    let system = System::new();
+    init_resource_storage(&system, ADMIN);
+    setup_catalog(&system);
+    let resource_storage = system.get_program(1);
+
+    let slot_resource = Resource::Slot(SlotResource {
+        src: String::from("src"),
+        thumb: String::from("thumb"),
+        metadata_uri: String::from("metadata_uri"),
+        base: CATALOG_ID.into(),
+        slot: 3,
+        media: None,
+    });
+    let composed_resource = Resource::Composed(ComposedResource {
+        src: String::from("src"),
+        thumb: String::from("thumb"),
+        metadata_uri: String::from("metadata_uri"),
+        base: CATALOG_ID.into(),
+        parts: vec![1],
+        media: None,
+    });
+    for (resource_id, resource) in [
+        (1, basic_resource()),
+        (2, slot_resource.clone()),
+        (3, composed_resource.clone()),
+        (4, basic_resource()),
+    ] {
+        let result = resource_storage.send(
+            ADMIN,
+            ResourceAction::AddResourceEntry {
+                resource_id,
+                resource,
+                metadata_hash: None,
+            },
+        );
+        assert!(!result.main_failed());
+    }
+
+    let resource: Option<Resource> = resource_storage
+        .read_state_using_wasm("resource", WASM_BINARY.into(), Some(2 as ResourceId))
+        .expect("Failed to read state");
+    assert_eq!(resource, Some(slot_resource.clone()));
+
+    let resource: Option<Resource> = resource_storage
+        .read_state_using_wasm("resource", WASM_BINARY.into(), Some(10 as ResourceId))
+        .expect("Failed to read state");
+    assert_eq!(resource, None);
+
+    let page: Vec<(ResourceId, Resource)> = resource_storage
+        .read_state_using_wasm("resources", WASM_BINARY.into(), Some((1_u32, 2_u32)))
+        .expect("Failed to read state");
+    assert_eq!(
+        page,
+        vec![(2, slot_resource.clone()), (3, composed_resource.clone())]
+    );
+
+    let basic_resources: Vec<(ResourceId, Resource)> = resource_storage
+        .read_state_using_wasm(
+            "resources_by_type",
+            WASM_BINARY.into(),
+            Some(ResourceKind::Basic),
+        )
+        .expect("Failed to read state");
+    assert_eq!(
+        basic_resources,
+        vec![(1, basic_resource()), (4, basic_resource())]
+    );
+
+    let base_resources: Vec<(ResourceId, Resource)> = resource_storage
+        .read_state_using_wasm(
+            "resources_for_base",
+            WASM_BINARY.into(),
+            Some(BaseId::from(CATALOG_ID)),
+        )
+        .expect("Failed to read state");
+    assert_eq!(
+        base_resources,
+        vec![(2, slot_resource), (3, composed_resource)]
+    );
+}
+
+#[test]
+fn media_descriptor() {
+    let system = System::new();
+    init_resource_storage(&system, ADMIN);
+    let resource_storage = system.get_program(1);
+
+    let mut media = MediaDescriptor {
+        mime_type: String::from("image/png"),
+        width: Some(512),
+        height: Some(512),
+        content_hash: Some([7; 32]),
+        license: Some(String::from("CC-BY-4.0")),
+    };
+
+    // must fail since the media type is missing or the dimensions are zero
+    for invalid_media in [
+        MediaDescriptor {
+            mime_type: String::new(),
+            ..media.clone()
+        },
+        MediaDescriptor {
+            width: Some(0),
+            ..media.clone()
+        },
+        MediaDescriptor {
+            height: Some(0),
+            ..media.clone()
+        },
+    ] {
+        let result = resource_storage.send(
+            ADMIN,
+            ResourceAction::AddResourceEntry {
+                resource_id: 1,
+                resource: Resource::Basic(BasicResource {
+                    src: String::from("src"),
+                    thumb: None,
+                    metadata_uri: String::from("metadata_uri"),
+                    media: Some(invalid_media),
+                }),
+                metadata_hash: None,
+            },
+        );
+        let expected_reply: Result<ResourceEvent, ResourceError> =
+            Err(ResourceError::WrongMediaDescriptor);
+        assert!(result.contains(&(ADMIN, expected_reply.encode())));
+    }
+
+    let resource = Resource::Basic(BasicResource {
+        src: String::from("src"),
+        thumb: None,
+        metadata_uri: String::from("metadata_uri"),
+        media: Some(media.clone()),
+    });
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: resource.clone(),
+            metadata_hash: None,
+        },
+    );
+    assert!(!result.main_failed());
+
+    // the media descriptor is returned with the resource
+    let result = resource_storage.send(ADMIN, ResourceAction::GetResource { id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::Resource(resource));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // the hash of the previous content is dropped when the source is changed
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::UpdateResource {
+            resource_id: 1,
+            src: Some(String::from("new_src")),
+            content_hash: None,
+            thumb: None,
+            metadata_uri: None,
+            metadata_hash: None,
+        },
+    );
+    assert!(!result.main_failed());
+
+    media.content_hash = None;
+    let resource = Resource::Basic(BasicResource {
+        src: String::from("new_src"),
+        thumb: None,
+        metadata_uri: String::from("metadata_uri"),
+        media: Some(media.clone()),
+    });
+    let result = resource_storage.send(ADMIN, ResourceAction::GetResource { id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::Resource(resource));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // the new hash is stored together with the new source
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::UpdateResource {
+            resource_id: 1,
+            src: Some(String::from("newer_src")),
+            content_hash: Some([8; 32]),
+            thumb: None,
+            metadata_uri: None,
+            metadata_hash: None,
+        },
+    );
+    assert!(!result.main_failed());
+
+    media.content_hash = Some([8; 32]);
+    let resource = Resource::Basic(BasicResource {
+        src: String::from("newer_src"),
+        thumb: None,
+        metadata_uri: String::from("metadata_uri"),
+        media: Some(media),
+    });
+    let result = resource_storage.send(ADMIN, ResourceAction::GetResource { id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::Resource(resource));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn resource_metadata_hash() {
+    let system = System::new();
+    init_resource_storage(&system, ADMIN);
+    let resource_storage = system.get_program(1);
+    let metadata_hash = [7; 32];
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: basic_resource(),
+            metadata_hash: Some(metadata_hash),
+        },
+    );
+    assert!(!result.main_failed());
+
+    let metadata: Option<(String, Option<[u8; 32]>)> = resource_storage
+        .read_state_using_wasm(
+            "resource_metadata",
+            WASM_BINARY.into(),
+            Some(1 as ResourceId),
+        )
+        .expect("Failed to read state");
+    assert_eq!(
+        metadata,
+        Some((String::from("metadata_uri"), Some(metadata_hash)))
+    );
+
+    // the hash of the previous metadata is dropped when the URI is changed
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::UpdateResource {
+            resource_id: 1,
+            src: None,
+            content_hash: None,
+            thumb: None,
+            metadata_uri: Some(String::from("new_metadata_uri")),
+            metadata_hash: None,
+        },
+    );
+    assert!(!result.main_failed());
+
+    let metadata: Option<(String, Option<[u8; 32]>)> = resource_storage
+        .read_state_using_wasm(
+            "resource_metadata",
+            WASM_BINARY.into(),
+            Some(1 as ResourceId),
+        )
+        .expect("Failed to read state");
+    assert_eq!(metadata, Some((String::from("new_metadata_uri"), None)));
+
+    // the new hash is stored together with the new URI
+    let new_metadata_hash = [8; 32];
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::UpdateResource {
+            resource_id: 1,
+            src: None,
+            content_hash: None,
+            thumb: None,
+            metadata_uri: Some(String::from("newer_metadata_uri")),
+            metadata_hash: Some(new_metadata_hash),
+        },
+    );
+    assert!(!result.main_failed());
+
+    let metadata: Option<(String, Option<[u8; 32]>)> = resource_storage
+        .read_state_using_wasm(
+            "resource_metadata",
+            WASM_BINARY.into(),
+            Some(1 as ResourceId),
+        )
+        .expect("Failed to read state");
+    assert_eq!(
+        metadata,
+        Some((String::from("newer_metadata_uri"), Some(new_metadata_hash)))
+    );
+
+    // must fail since the resource has no media descriptor to hold the content hash
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::UpdateResource {
+            resource_id: 1,
+            src: None,
+            content_hash: Some([9; 32]),
+            thumb: None,
+            metadata_uri: None,
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::WrongMediaDescriptor);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn shared_resource_storage() {
+    let system = System::new();
+    init_resource_storage(&system, ADMIN);
+    let resource_storage = system.get_program(1);
+
+    let result = resource_storage.send(
+        ADMIN,
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: basic_resource(),
+            metadata_hash: None,
+        },
+    );
+    assert!(!result.main_failed());
+
+    // must fail since the collection is not allowed
+    let result = resource_storage.send(
+        COLLECTION,
+        ResourceAction::AddTokenReference {
+            resource_id: 1,
+            token_id: 5.into(),
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::NotAllowedToCall);
+    assert!(result.contains(&(COLLECTION, expected_reply.encode())));
+
+    // must fail since only the admin can allow collections
+    let result = resource_storage.send(USER, ResourceAction::AllowCollection(COLLECTION.into()));
+    let expected_reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::NotAllowedToCall);
+    assert!(result.contains(&(USER, expected_reply.encode())));
+
+    let result = resource_storage.send(ADMIN, ResourceAction::AllowCollection(COLLECTION.into()));
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::CollectionAllowed(COLLECTION.into()));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    let result = resource_storage.send(
+        COLLECTION,
+        ResourceAction::AddTokenReference {
+            resource_id: 1,
+            token_id: 5.into(),
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::TokenReferenceAdded {
+            resource_id: 1,
+            token_id: 5.into(),
+        });
+    assert!(result.contains(&(COLLECTION, expected_reply.encode())));
+
+    let state: ResourceState = resource_storage
+        .read_state()
+        .expect("Failed to decode the state");
+    assert_eq!(
+        state.token_references,
+        vec![(1, vec![(COLLECTION.into(), 5.into())])]
+    );
+    assert_eq!(state.collections, vec![COLLECTION.into()]);
+
+    // must fail since only the admin can add resources
+    let result = resource_storage.send(
+        COLLECTION,
+        ResourceAction::AddResourceEntry {
+            resource_id: 2,
+            resource: basic_resource(),
+            metadata_hash: None,
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::NotAllowedToCall);
+    assert!(result.contains(&(COLLECTION, expected_reply.encode())));
+
+    // must fail since the collection references the resource
+    let result = resource_storage.send(ADMIN, ResourceAction::RemoveResource { resource_id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Err(ResourceError::ResourceIsReferenced);
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // the resources can be read by anyone
+    let result = resource_storage.send(USER, ResourceAction::GetResource { id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::Resource(basic_resource()));
+    assert!(result.contains(&(USER, expected_reply.encode())));
+
+    let result =
+        resource_storage.send(ADMIN, ResourceAction::DisallowCollection(COLLECTION.into()));
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::CollectionDisallowed(COLLECTION.into()));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+
+    // must fail since the collection is disallowed
+    let result = resource_storage.send(
+        COLLECTION,
+        ResourceAction::AddTokenReference {
+            resource_id: 1,
+            token_id: 6.into(),
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::NotAllowedToCall);
+    assert!(result.contains(&(COLLECTION, expected_reply.encode())));
+
+    // the disallowed collection still releases its references
+    let result = resource_storage.send(
+        COLLECTION,
+        ResourceAction::RemoveTokenReference {
+            resource_id: 1,
+            token_id: 5.into(),
+        },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::TokenReferenceRemoved {
+            resource_id: 1,
+            token_id: 5.into(),
+        });
+    assert!(result.contains(&(COLLECTION, expected_reply.encode())));
+
+    let result = resource_storage.send(ADMIN, ResourceAction::RemoveResource { resource_id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::ResourceRemoved(1));
+    assert!(result.contains(&(ADMIN, expected_reply.encode())));
+}
+
+#[test]
+fn storage_owner() {
+    let system = System::new();
+    system.init_logger();
+    let resource_storage = Program::current(&system);
+    let res = resource_storage.send(
+        ADMIN,
+        InitResource {
+            resource_name: "ResourceName".to_string(),
+            owner: Some(USER.into()),
+        },
+    );
+    assert!(!res.main_failed());
+
+    let state: ResourceState = resource_storage
+        .read_state()
+        .expect("Failed to decode ResourceState");
+    assert_eq!(state.owner, Some(USER.into()));
+
+    // the owner manages the storage together with the admin
+    for (resource_id, account) in [(1, ADMIN), (2, USER)] {
+        let result = resource_storage.send(
+            account,
+            ResourceAction::AddResourceEntry {
+                resource_id,
+                resource: basic_resource(),
+                metadata_hash: None,
+            },
+        );
+        let expected_reply: Result<ResourceEvent, ResourceError> =
+            Ok(ResourceEvent::ResourceEntryAdded {
+                resource_id,
+                resource: basic_resource(),
+            });
+        assert!(result.contains(&(account, expected_reply.encode())));
+    }
+
+    let result = resource_storage.send(USER, ResourceAction::RemoveResource { resource_id: 1 });
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::ResourceRemoved(1));
+    assert!(result.contains(&(USER, expected_reply.encode())));
+
+    let result = resource_storage.send(USER, ResourceAction::AllowCollection(COLLECTION.into()));
+    let expected_reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::CollectionAllowed(COLLECTION.into()));
+    assert!(result.contains(&(USER, expected_reply.encode())));
+
+    // other accounts can't manage the storage
+    let result = resource_storage.send(
+        COLLECTION,
+        ResourceAction::RemoveResource { resource_id: 2 },
+    );
+    let expected_reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::NotAllowedToCall);
+    assert!(result.contains(&(COLLECTION, expected_reply.encode())));
+}
@@ -1,9 +1,13 @@
 #![no_std]
+use catalog_io::Part;
 use gmeta::{In, InOut, Metadata};
 use gstd::{prelude::*, ActorId};
 use primitive_types::U256;
+use resource_io::{Resource, ResourceError};
 use types::primitives::*;
 pub type TokenEquipment = Vec<(PartId, Equipment)>;
+/// The slot of the equipment: the token ID, the catalog address and the slot part ID.
+pub type EquipmentSlot = (TokenId, ActorId, PartId);
 pub struct RMRKMetadata;
 
 impl Metadata for RMRKMetadata {
@@ -27,18 +31,28 @@ pub struct RMRKState {
     pub children_status: Vec<(CollectionAndToken, ChildStatus)>,
     pub balances: Vec<(ActorId, U256)>,
     pub assets: AssetsState,
+    pub resource_storage_id: ActorId,
+    pub multiresource: MultiResourceState,
 }
 
 #[derive(Default, Encode, Debug, Decode, TypeInfo)]
 pub struct AssetsState {
     /// Mapping of uint64 Ids to asset metadata
     pub assets: Vec<(u64, String)>,
+    /// Mapping of asset ID to the version of the asset entry, incremented on each update
+    pub asset_versions: Vec<(u64, u32)>,
+    /// Mapping of asset ID to the hash of the content behind its metadata URI
+    pub asset_hashes: Vec<(u64, [u8; 32])>,
     /// Mapping of uint64 asset ID to corresponding catalog address.
     pub catalog_addresses: Vec<(u64, ActorId)>,
     /// Mapping of asset_id to equippable_group_ids.
     pub equippable_group_ids: Vec<(u64, u64)>,
     /// Mapping of asset_id to catalog parts applicable to this asset, both fixed and slot
     pub part_ids: Vec<(u64, Vec<PartId>)>,
+    /// Mapping of asset_id to non-slot parts of this asset, as reported by the catalog when the entry was added
+    pub fixed_part_ids: Vec<(u64, Vec<PartId>)>,
+    /// Mapping of asset_id to slot parts of this asset, as reported by the catalog when the entry was added
+    pub slot_part_ids: Vec<(u64, Vec<PartId>)>,
     /// Mapping of tokenId to an array of pending assets
     pub pending_assets: Vec<(TokenId, Vec<u64>)>,
     /// Mapping of tokenId to an array of active assets
@@ -47,13 +61,15 @@ pub struct AssetsState {
     pub active_assets_priorities: Vec<(TokenId, Vec<u64>)>,
     /// Mapping of tokenId to new asset, to asset to be replaced
     pub asset_replacement: Vec<(TokenId, Vec<(u64, u64)>)>,
+    /// Mapping of tokenId to the account whose proposed assets are accepted automatically
+    pub trusted_issuers: Vec<(TokenId, ActorId)>,
     /// Mapping of `equippable_group_id` to parent contract address and valid `slot_id`.
     pub valid_parent_slots: Vec<(u64, Vec<(ActorId, PartId)>)>,
     /// Mapping of token ID and catalog address to slot part ID to equipment information.
     /// Used to compose an NFT.
     pub equipments: Vec<((TokenId, ActorId), TokenEquipment)>,
 }
-#[derive(Default, Debug, Clone, Encode, Decode, TypeInfo)]
+#[derive(Default, Debug, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
 pub struct Equipment {
     ///  The ID of the asset equipping a child
     pub asset_id: u64,
@@ -64,6 +80,45 @@ pub struct Equipment {
     /// Address of the collection to which the child asset belongs to
     pub child_id: ActorId,
 }
+/// Data required to render the composed NFT.
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct ComposedEquippables {
+    /// Metadata URI of the composed asset
+    pub metadata_uri: String,
+    /// ID of the equippable group of the composed asset
+    pub equippable_group_id: u64,
+    /// Address of the catalog the composed asset belongs to
+    pub catalog_address: ActorId,
+    /// Fixed parts of the composed asset
+    pub fixed_parts: Vec<FixedPartData>,
+    /// Slot parts of the composed asset with the equipped child assets
+    pub slot_parts: Vec<EquippedSlotPart>,
+    /// Text, palette and animation parts of the composed asset
+    pub other_parts: Vec<(PartId, Part)>,
+}
+
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct FixedPartData {
+    /// ID of the fixed part in the catalog
+    pub part_id: PartId,
+    /// zIndex of the part layer
+    pub z: Option<ZIndex>,
+    /// Metadata URI of the part
+    pub metadata_uri: String,
+}
+
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo, PartialEq, Eq)]
+pub struct EquippedSlotPart {
+    /// ID of the slot part in the catalog
+    pub part_id: PartId,
+    /// zIndex of the part layer
+    pub z: Option<ZIndex>,
+    /// Metadata URI of the part
+    pub metadata_uri: String,
+    /// The child asset equipped into the slot (if any)
+    pub equipment: Option<Equipment>,
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo, Clone)]
 pub struct RMRKOwner {
     pub token_id: Option<TokenId>,
@@ -82,8 +137,14 @@ pub struct MultiResourceState {
 pub struct InitRMRK {
     pub name: String,
     pub symbol: String,
+    /// The name of the resource storage created from `resource_hash`.
     pub resource_name: String,
+    /// The code hash of the resource storage program created on init.
     pub resource_hash: Option<[u8; 32]>,
+    /// The address of an already deployed resource storage program.
+    /// The collection must be allowed by the storage owner to reference its resources.
+    /// Can not be set together with `resource_hash`, otherwise the initialization fails.
+    pub resource_address: Option<ActorId>,
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo, Copy, Eq, PartialEq)]
@@ -331,31 +392,206 @@ pub enum RMRKAction {
 
     /// Used to add an equippable asset entry.
     ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * `asset_id` can not be equal to zero.
+    /// * Asset with indicated `asset_id` must not exist.
+    ///
     /// Arguments:
+    /// * `asset_id`: ID of the new asset
     /// * `equippable_group_id`: ID of the equippable group
     /// * `catalog_address`: Address of the `Catalog` smart contract this asset belongs to
     /// * `metadata_uri`: Metadata URI of the asset
     /// * `parts_ids`:  An array of IDs of fixed and slot parts to be included in the asset
+    /// * `metadata_hash`: Optional 32-byte hash of the content behind `metadata_uri`
     ///
-    /// On success reply `[RMRKEvent::ResourceEntryAdded]`.
+    /// On success reply `[RMRKReply::EquippableAssetEntryAdded]`.
     AddEquippableAssetEntry {
+        asset_id: u64,
         equippable_group_id: u64,
         catalog_address: Option<ActorId>,
         metadata_uri: String,
         part_ids: Vec<PartId>,
+        metadata_hash: Option<[u8; 32]>,
+    },
+
+    /// Updates the metadata URI and/or part IDs of the asset entry.
+    /// Each update increments the version of the asset entry.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * Asset with indicated `asset_id` must exist.
+    /// * The asset must have a catalog address if new `part_ids` are not empty.
+    /// * New `part_ids` must exist in the catalog of the asset.
+    /// * At least one of `metadata_uri`, `metadata_hash` and `part_ids` must be set.
+    ///
+    /// Arguments:
+    /// * `asset_id`: ID of the asset
+    /// * `metadata_uri`: New metadata URI of the asset (if any),
+    ///   the hash of the previous metadata is dropped when the URI is changed without a new hash
+    /// * `metadata_hash`: New 32-byte hash of the content behind the metadata URI (if any)
+    /// * `part_ids`: New IDs of fixed and slot parts of the asset (if any)
+    ///
+    /// On success reply `[RMRKReply::AssetEntryUpdated]`.
+    UpdateAssetEntry {
+        asset_id: u64,
+        metadata_uri: Option<String>,
+        metadata_hash: Option<[u8; 32]>,
+        part_ids: Option<Vec<PartId>>,
     },
 
+    /// Proposes an asset to the token.
+    /// The asset is added to the pending array of the token.
+    /// If the `msg::source()` is the root owner of the token or its trusted issuer,
+    /// the asset is accepted immediately.
+    /// If that asset replaces an asset of a nested token, the parent collection updates
+    /// its equipments first and the asset isn't proposed if the parent fails.
+    ///
+    /// # Requirements:
+    /// * The token with indicated `token_id` must exist.
+    /// * The asset with indicated `asset_id` must exist.
+    /// * The asset must be neither pending nor active for the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `asset_id`: ID of the asset.
+    /// * `replaces_asset_with_id`: ID of the active asset to be replaced on acceptance (0 if none).
+    ///
+    /// On success replies [`RMRKReply::AssetAddedToToken`] or [`RMRKReply::AssetAccepted`] if the asset was accepted.
     AddAssetToToken {
         token_id: TokenId,
         asset_id: u64,
         replaces_asset_with_id: u64,
     },
 
+    /// Accepts an asset from the pending array of the token.
+    /// If the asset replaces an active asset, the equipments of the replaced asset
+    /// are moved to the new asset if it still fits the slot, otherwise they are removed.
+    /// The equipments in the parent collection of a nested token are updated
+    /// through [`RMRKAction::ReplaceChildAsset`].
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The asset must exist in the pending array of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `asset_id`: ID of the accepted asset.
+    ///
+    /// On success replies [`RMRKReply::AssetAccepted`] or [`RMRKReply::AssetReplaced`].
     AcceptAsset {
         token_id: TokenId,
         asset_id: u64,
     },
 
+    /// Sets the account whose proposed assets are accepted automatically for the token.
+    /// The trusted issuer is reset when the token is transferred or burnt.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `issuer`: the trusted account (`None` removes the trusted issuer).
+    ///
+    /// On success replies [`RMRKReply::TrustedIssuerSet`].
+    SetTrustedIssuer {
+        token_id: TokenId,
+        issuer: Option<ActorId>,
+    },
+
+    /// Adds resource entry to the resource storage contract of the collection.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * The resource storage must be created or attached on init.
+    /// * The RMRK contract must be the admin or the owner of an attached storage;
+    ///   otherwise the resources are added to that storage directly by its owner.
+    ///
+    /// # Arguments:
+    /// * `resource_id`: is a resource identifier.
+    /// * `resource`: is a resource struct that can be `Basic`, `Slot` or `Composed`.
+    /// * `metadata_hash`: optional 32-byte hash of the content behind the metadata URI.
+    ///
+    /// On success replies [`RMRKReply::ResourceEntryAdded`].
+    AddResourceEntry {
+        resource_id: ResourceId,
+        resource: Resource,
+        metadata_hash: Option<[u8; 32]>,
+    },
+
+    /// Adds the resource from the resource storage to the pending resources of the token.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * The token must exist.
+    /// * The resource must exist in the resource storage.
+    /// * The collection must be allowed by an attached resource storage.
+    /// * The resource must be neither pending nor active for the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `resource_id`: is a resource identifier.
+    /// * `overwrite_id`: ID of the resource to be overwritten when the new resource is accepted (0 if none).
+    ///
+    /// On success replies [`RMRKReply::ResourceAddedToToken`].
+    AddResourceToToken {
+        token_id: TokenId,
+        resource_id: ResourceId,
+        overwrite_id: ResourceId,
+    },
+
+    /// Accepts the resource from the pending resources of the token.
+    /// If the resource overwrites an active resource, it takes its position and priority,
+    /// and the overwritten resource is released in the resource storage.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The resource must exist in the pending array of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `resource_id`: ID of the accepted resource.
+    ///
+    /// On success replies [`RMRKReply::ResourceAccepted`].
+    AcceptResource {
+        token_id: TokenId,
+        resource_id: ResourceId,
+    },
+
+    /// Rejects the resource from the pending resources of the token
+    /// and releases it in the resource storage.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The resource must exist in the pending array of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `resource_id`: ID of the rejected resource.
+    ///
+    /// On success replies [`RMRKReply::ResourceRejected`].
+    RejectResource {
+        token_id: TokenId,
+        resource_id: ResourceId,
+    },
+
+    /// Sets the priorities of the active resources of the token.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The length of `priorities` must be equal to the number of active resources.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `priorities`: the priorities of the active resources in the order of the active array.
+    ///
+    /// On success replies [`RMRKReply::PrioritySet`].
+    SetResourcePriority {
+        token_id: TokenId,
+        priorities: Vec<u8>,
+    },
+
     /// Declares that the assets belonging to a given `equippable_group_id` are
     /// equippable into the `Slot` associated with the `part_id` of the collection
     ///  at the specified `parent_id`.
@@ -391,6 +627,45 @@ pub enum RMRKAction {
         asset_id: u64,
         slot_part_id: PartId,
     },
+
+    /// Gathers the data required to render the composed NFT:
+    /// the fixed and slot parts of the asset from the catalog and the child assets equipped into the slots.
+    ///
+    /// # Requirements:
+    /// * The asset with indicated `asset_id` must be active for the token.
+    /// * The asset must have a catalog address.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `asset_id`: ID of the composed asset.
+    ///
+    /// On success replies [`RMRKReply::ComposedEquippables`].
+    ComposeEquippables {
+        token_id: TokenId,
+        asset_id: u64,
+    },
+
+    /// Updates the equipments holding the replaced asset of the child token.
+    /// Sent by the child collection when an asset of a nested token is replaced:
+    /// the equipment is moved to the new asset if the new asset fits the same slot,
+    /// otherwise the equipment is removed.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the child collection.
+    ///
+    /// # Arguments:
+    /// * `child_token_id`: the tokenId of the child NFT.
+    /// * `old_asset_id`: ID of the replaced child asset.
+    /// * `new_asset_id`: ID of the new child asset.
+    /// * `slot_part_id`: the slot the new asset can be equipped into (`None` if it can not be equipped).
+    ///
+    /// On success replies [`RMRKReply::ChildAssetReplaced`].
+    ReplaceChildAsset {
+        child_token_id: TokenId,
+        old_asset_id: u64,
+        new_asset_id: u64,
+        slot_part_id: Option<PartId>,
+    },
 }
 
 #[derive(Debug, Encode, Decode, TypeInfo, PartialEq)]
@@ -419,12 +694,31 @@ pub enum RMRKReply {
     TokenEquipped,
     EquippableIsOk,
     EquippableAssetEntryAdded,
+    AssetEntryUpdated {
+        asset_id: u64,
+        version: u32,
+    },
     AssetAddedToToken,
     AssetAccepted,
+    AssetReplaced {
+        replaced_asset_id: u64,
+        migrated_equipments: Vec<EquipmentSlot>,
+        unequipped_equipments: Vec<EquipmentSlot>,
+    },
+    TrustedIssuerSet,
     ValidParentEquippableGroupIdSet,
     TokenBeEquippedWithAssetIntoSlot,
     ChildAssetEquipped,
     AssetSet,
+    ComposedEquippables(ComposedEquippables),
+    ResourceEntryAdded(ResourceId),
+    ResourceAddedToToken,
+    ResourceAccepted,
+    ResourceRejected,
+    ChildAssetReplaced {
+        migrated_equipments: Vec<EquipmentSlot>,
+        unequipped_equipments: Vec<EquipmentSlot>,
+    },
 }
 
 #[derive(Debug, Encode, Decode, TypeInfo, PartialEq, Eq, Clone)]
@@ -457,4 +751,30 @@ pub enum RMRKError {
     NotApprovedAccount,
     NotRootOwner,
     ErrorInCatalog,
+    NotIssuer,
+    PartDoesNotExistInCatalog,
+    ResourceStorageNotSet,
+    ErrorInResourceStorage,
+    ResourceAlreadyExists,
+    ResourceDoesNotExist,
+    ResourceDoesNotExistInPendingArray,
+    BadPriorityListLength,
+    NothingToUpdate,
+    NotAllowedInResourceStorage,
+    ParentDidNotReply,
+    WrongMediaDescriptor,
+}
+
+impl From<ResourceError> for RMRKError {
+    fn from(error: ResourceError) -> Self {
+        match error {
+            ResourceError::ResourceAlreadyExists => RMRKError::ResourceAlreadyExists,
+            ResourceError::ResourceDoesNotExist => RMRKError::ResourceDoesNotExist,
+            ResourceError::PartDoesNotExistInCatalog => RMRKError::PartDoesNotExistInCatalog,
+            ResourceError::WrongPartFormat => RMRKError::WrongPartFormat,
+            ResourceError::NotAllowedToCall => RMRKError::NotAllowedInResourceStorage,
+            ResourceError::WrongMediaDescriptor => RMRKError::WrongMediaDescriptor,
+            _ => RMRKError::ErrorInResourceStorage,
+        }
+    }
 }
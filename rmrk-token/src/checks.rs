@@ -40,6 +40,14 @@ impl RMRKToken {
         Ok(())
     }
 
+    /// Checks that `msg::source()` is the collection issuer
+    pub fn check_admin(&self) -> Result<(), RMRKError> {
+        if msg::source() != self.admin {
+            return Err(RMRKError::NotIssuer);
+        }
+        Ok(())
+    }
+
     /// Checks that `msg::source()` is the owner of the token with indicated `token_id`
     pub fn assert_owner(&self, root_owner: &ActorId) {
         if msg::source() != *root_owner {
@@ -55,6 +63,16 @@ impl RMRKToken {
         }
     }
 
+    /// Returns the parent collection of the token
+    /// if the token is nested into a token of another collection
+    pub fn get_parent_collection(&self, token_id: TokenId) -> Result<Option<ActorId>, RMRKError> {
+        let rmrk_owner = self.get_rmrk_owner(token_id)?;
+        if rmrk_owner.token_id.is_some() && rmrk_owner.owner_id != exec::program_id() {
+            return Ok(Some(rmrk_owner.owner_id));
+        }
+        Ok(None)
+    }
+
     pub fn get_child_status(
         &self,
         child_token: (CollectionId, TokenId),
@@ -1,17 +1,29 @@
 use crate::*;
+use catalog_io::{FixedPart, Part, SlotPart};
 use gstd::msg;
-use rmrk_io::Equipment;
+use rmrk_io::{ComposedEquippables, Equipment, EquipmentSlot, EquippedSlotPart, FixedPartData};
+
+/// The maximum number of pending assets a token can have.
+pub const MAX_PENDING_ASSETS: usize = 128;
 
 #[derive(Default)]
 pub struct Assets {
     /// Mapping of uint64 Ids to asset metadata
     pub assets: HashMap<u64, String>,
+    /// Mapping of asset ID to the version of the asset entry, incremented on each update
+    pub asset_versions: HashMap<u64, u32>,
+    /// Mapping of asset ID to the hash of the content behind its metadata URI
+    pub asset_hashes: HashMap<u64, [u8; 32]>,
     /// Mapping of uint64 asset ID to corresponding catalog address.
     pub catalog_addresses: HashMap<u64, ActorId>,
     /// Mapping of asset_id to equippable_group_ids.
     pub equippable_group_ids: HashMap<u64, u64>,
     /// Mapping of asset_id to catalog parts applicable to this asset, both fixed and slot
     pub part_ids: HashMap<u64, Vec<PartId>>,
+    /// Mapping of asset_id to non-slot parts of this asset, as reported by the catalog when the entry was added
+    pub fixed_part_ids: HashMap<u64, Vec<PartId>>,
+    /// Mapping of asset_id to slot parts of this asset, as reported by the catalog when the entry was added
+    pub slot_part_ids: HashMap<u64, Vec<PartId>>,
     /// Mapping of tokenId to an array of pending assets
     pub pending_assets: HashMap<TokenId, Vec<u64>>,
     /// Mapping of tokenId to an array of active assets
@@ -20,6 +32,8 @@ pub struct Assets {
     pub active_assets_priorities: HashMap<TokenId, Vec<u64>>,
     /// Mapping of tokenId to new asset, to asset to be replaced
     pub asset_replacement: HashMap<TokenId, HashMap<u64, u64>>,
+    /// Mapping of tokenId to the account whose proposed assets are accepted automatically
+    pub trusted_issuers: HashMap<TokenId, ActorId>,
     /// Mapping of `equippable_group_id` to parent contract address and valid `slot_id`.
     pub valid_parent_slots: HashMap<u64, HashMap<ActorId, PartId>>,
     /// Mapping of token ID and catalog address to slot part ID to equipment information.
@@ -28,41 +42,270 @@ pub struct Assets {
 }
 
 impl Assets {
+    /// Adds an equippable asset entry.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * `asset_id` can not be equal to zero.
+    /// * Asset with indicated `asset_id` must not exist.
+    /// * `catalog_address` must be set if `part_ids` is not empty.
+    /// * The catalog must reply to `CheckParts` and all `part_ids` must exist in it.
+    ///
+    /// The collection is registered in the catalog as a dependent of the asset parts,
+    /// so that the parts can not be removed from the catalog without the `force` flag.
+    ///
+    /// # Arguments:
+    /// * `asset_id`: ID of the new asset.
+    /// * `equippable_group_id`: ID of the equippable group.
+    /// * `catalog_address`: address of the `Catalog` smart contract this asset belongs to.
+    /// * `metadata_uri`: metadata URI of the asset.
+    /// * `part_ids`: an array of IDs of fixed and slot parts to be included in the asset.
+    /// * `metadata_hash`: optional 32-byte hash of the content behind `metadata_uri`.
+    ///
+    /// On success replies [`RMRKReply::EquippableAssetEntryAdded`].
     pub fn add_equippable_asset_entry(
         &mut self,
+        tx_manager: &mut TxManager,
+        asset_id: u64,
         equippable_group_id: u64,
         catalog_address: Option<ActorId>,
         metadata_uri: String,
         part_ids: Vec<PartId>,
+        metadata_hash: Option<[u8; 32]>,
     ) -> Result<RMRKReply, RMRKError> {
-        let id = (self.assets.len() as u64) + 1;
         if catalog_address.is_none() && !part_ids.is_empty() {
             return Err(RMRKError::CatalogRequiredForParts);
         }
+        self.check_new_asset_id(asset_id)?;
+        let mut parts = Vec::new();
         if let Some(address) = catalog_address {
-            self.catalog_addresses.insert(id, address);
-            self.equippable_group_ids.insert(id, equippable_group_id);
-            self.part_ids.insert(id, part_ids);
+            // the catalog is queried even without parts to make sure it exists
+            match tx_manager.get_state(msg::id()) {
+                TxState::Initial => {
+                    let msg_id = check_parts_msg(&address, part_ids.clone());
+                    tx_manager.set_tx_state(TxState::MsgCheckPartsSent, msg_id);
+                    exec::wait_for(5);
+                }
+                TxState::ReplyCheckPartsReceived => {
+                    if !part_ids.is_empty() {
+                        let msg_id = register_dependent_msg(&address, part_ids.clone());
+                        tx_manager.set_tx_state(TxState::MsgRegisterDependentSent, msg_id);
+                        exec::wait_for(5);
+                    }
+                    parts = tx_manager.get_decoded_data::<Vec<Part>>()?;
+                }
+                TxState::ReplyDependentRegistered => {
+                    parts = tx_manager.get_decoded_data::<Vec<Part>>()?;
+                }
+                // the message was woken up by timeout, so the catalog did not reply
+                TxState::MsgCheckPartsSent => return Err(RMRKError::CatalogDoesNotExist),
+                TxState::MsgRegisterDependentSent => return Err(RMRKError::ErrorInCatalog),
+                TxState::Error(error) => return Err(error),
+                _ => {
+                    unreachable!()
+                }
+            }
+        }
+        self._add_asset_entry(asset_id, metadata_uri)?;
+        if let Some(metadata_hash) = metadata_hash {
+            self.asset_hashes.insert(asset_id, metadata_hash);
+        }
+        if let Some(address) = catalog_address {
+            self.catalog_addresses.insert(asset_id, address);
+            self.equippable_group_ids
+                .insert(asset_id, equippable_group_id);
+            self.set_part_ids(asset_id, part_ids, parts);
         }
-        self._add_asset_entry(id, metadata_uri)?;
         Ok(RMRKReply::EquippableAssetEntryAdded)
     }
 
+    /// Returns the current parts of the asset that are neither among its new parts
+    /// nor used by other assets of the collection from the same catalog.
+    fn unused_part_ids(
+        &self,
+        asset_id: u64,
+        catalog_address: &ActorId,
+        new_part_ids: &[PartId],
+    ) -> Vec<PartId> {
+        let part_ids = if let Some(part_ids) = self.part_ids.get(&asset_id) {
+            part_ids
+        } else {
+            return Vec::new();
+        };
+        part_ids
+            .iter()
+            .filter(|part_id| !new_part_ids.contains(part_id))
+            .filter(|part_id| {
+                !self.part_ids.iter().any(|(id, ids)| {
+                    *id != asset_id
+                        && self.catalog_addresses.get(id) == Some(catalog_address)
+                        && ids.contains(part_id)
+                })
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Records the part IDs of the asset split into fixed and slot parts
+    /// according to the parts reported by the catalog.
+    fn set_part_ids(&mut self, asset_id: u64, part_ids: Vec<PartId>, parts: Vec<Part>) {
+        let mut fixed_part_ids = Vec::new();
+        let mut slot_part_ids = Vec::new();
+        for (part_id, part) in part_ids.iter().zip(parts) {
+            match part {
+                Part::Slot(_) => slot_part_ids.push(*part_id),
+                _ => fixed_part_ids.push(*part_id),
+            }
+        }
+        self.part_ids.insert(asset_id, part_ids);
+        self.fixed_part_ids.insert(asset_id, fixed_part_ids);
+        self.slot_part_ids.insert(asset_id, slot_part_ids);
+    }
+
     fn _add_asset_entry(&mut self, id: u64, metadata_uri: String) -> Result<RMRKReply, RMRKError> {
+        self.check_new_asset_id(id)?;
+        self.assets.insert(id, metadata_uri);
+        self.asset_versions.insert(id, 0);
+        Ok(RMRKReply::AssetSet)
+    }
+
+    fn check_new_asset_id(&self, id: u64) -> Result<(), RMRKError> {
         if id == 0 {
             return Err(RMRKError::ZeroIdForbidden);
         }
-        if self.assets.insert(id, metadata_uri).is_some() {
+        if self.assets.contains_key(&id) {
             return Err(RMRKError::AssetAlreadyExists);
         }
-        Ok(RMRKReply::AssetSet)
+        Ok(())
     }
 
+    /// Updates the metadata URI and/or part IDs of the asset entry.
+    /// Each update increments the version of the asset entry.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * Asset with indicated `asset_id` must exist.
+    /// * The asset must have a catalog address if new `part_ids` are not empty.
+    /// * New `part_ids` must exist in the catalog of the asset.
+    /// * At least one of `metadata_uri`, `metadata_hash` and `part_ids` must be set.
+    ///
+    /// The collection is registered in the catalog as a dependent of the new parts
+    /// and unregistered from the parts that none of its assets use anymore.
+    ///
+    /// # Arguments:
+    /// * `asset_id`: ID of the asset.
+    /// * `metadata_uri`: new metadata URI of the asset (if any),
+    ///   the hash of the previous metadata is dropped unless a new hash is given.
+    /// * `metadata_hash`: new 32-byte hash of the content behind the metadata URI (if any).
+    /// * `part_ids`: new IDs of fixed and slot parts of the asset (if any).
+    ///
+    /// On success replies [`RMRKReply::AssetEntryUpdated`].
+    pub fn update_asset_entry(
+        &mut self,
+        tx_manager: &mut TxManager,
+        asset_id: u64,
+        metadata_uri: Option<String>,
+        metadata_hash: Option<[u8; 32]>,
+        part_ids: Option<Vec<PartId>>,
+    ) -> Result<RMRKReply, RMRKError> {
+        if !self.assets.contains_key(&asset_id) {
+            return Err(RMRKError::NoAssetMatchingId);
+        }
+        if metadata_uri.is_none() && metadata_hash.is_none() && part_ids.is_none() {
+            return Err(RMRKError::NothingToUpdate);
+        }
+        if let Some(part_ids) = part_ids {
+            let catalog_address = self.catalog_addresses.get(&asset_id).copied();
+            if catalog_address.is_none() && !part_ids.is_empty() {
+                return Err(RMRKError::CatalogRequiredForParts);
+            }
+            if let Some(address) = catalog_address {
+                // the collection no longer depends on the parts that are not used by its other assets
+                let unused_part_ids = self.unused_part_ids(asset_id, &address, &part_ids);
+                match tx_manager.get_state(msg::id()) {
+                    TxState::Initial if !part_ids.is_empty() => {
+                        let msg_id = check_parts_msg(&address, part_ids.clone());
+                        tx_manager.set_tx_state(TxState::MsgCheckPartsSent, msg_id);
+                        exec::wait_for(5);
+                    }
+                    TxState::ReplyCheckPartsReceived => {
+                        let msg_id = register_dependent_msg(&address, part_ids.clone());
+                        tx_manager.set_tx_state(TxState::MsgRegisterDependentSent, msg_id);
+                        exec::wait_for(5);
+                    }
+                    TxState::Initial | TxState::ReplyDependentRegistered
+                        if !unused_part_ids.is_empty() =>
+                    {
+                        let msg_id = unregister_dependent_msg(&address, unused_part_ids);
+                        tx_manager.set_tx_state(TxState::MsgUnregisterDependentSent, msg_id);
+                        exec::wait_for(5);
+                    }
+                    TxState::Initial
+                    | TxState::ReplyDependentRegistered
+                    | TxState::ReplyDependentUnregistered => {}
+                    // the message was woken up by timeout, so the catalog did not reply
+                    TxState::MsgCheckPartsSent
+                    | TxState::MsgRegisterDependentSent
+                    | TxState::MsgUnregisterDependentSent => return Err(RMRKError::ErrorInCatalog),
+                    TxState::Error(error) => return Err(error),
+                    _ => {
+                        unreachable!()
+                    }
+                }
+                let parts = if part_ids.is_empty() {
+                    Vec::new()
+                } else {
+                    tx_manager.get_decoded_data::<Vec<Part>>()?
+                };
+                self.set_part_ids(asset_id, part_ids, parts);
+            }
+        }
+        if let Some(metadata_uri) = metadata_uri {
+            self.assets.insert(asset_id, metadata_uri);
+            if metadata_hash.is_none() {
+                // the hash of the previous metadata does not match the new URI
+                self.asset_hashes.remove(&asset_id);
+            }
+        }
+        if let Some(metadata_hash) = metadata_hash {
+            self.asset_hashes.insert(asset_id, metadata_hash);
+        }
+        let version = self.asset_versions.entry(asset_id).or_default();
+        *version += 1;
+        Ok(RMRKReply::AssetEntryUpdated {
+            asset_id,
+            version: *version,
+        })
+    }
+
+    /// Proposes an asset to the token.
+    /// The asset is added to the pending array of the token
+    /// and becomes active only after it is accepted by the token owner.
+    /// If `auto_accept` is set, the asset is accepted immediately.
+    /// If the accepted asset replaces an asset of the token nested into a token of another collection,
+    /// the parent collection updates its equipments first and the asset isn't proposed if it fails.
+    ///
+    /// # Requirements:
+    /// * The asset with indicated `asset_id` must exist.
+    /// * The asset must be neither pending nor active for the token.
+    /// * The token can not have more than `MAX_PENDING_ASSETS` pending assets.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT the asset is added to.
+    /// * `asset_id`: ID of the asset.
+    /// * `replaces_asset_with_id`: ID of the active asset to be replaced on acceptance (0 if none).
+    /// * `auto_accept`: whether the asset must be accepted immediately.
+    /// * `parent_id`: the parent collection if the token is nested into a token of another collection.
+    ///
+    /// On success replies [`RMRKReply::AssetAddedToToken`] or [`RMRKReply::AssetAccepted`] if the asset was accepted.
     pub fn add_asset_to_token(
         &mut self,
+        tx_manager: &mut TxManager,
         token_id: TokenId,
         asset_id: u64,
         replaces_asset_with_id: u64,
+        auto_accept: bool,
+        parent_id: Option<ActorId>,
     ) -> Result<RMRKReply, RMRKError> {
         if !self.assets.contains_key(&asset_id) {
             return Err(RMRKError::NoAssetMatchingId);
@@ -72,15 +315,32 @@ impl Assets {
                 return Err(RMRKError::AssetAlreadyExists);
             }
         }
-        if let Some(pending_assets) = self.pending_assets.get_mut(&token_id) {
-            if pending_assets.len() >= 128 {
+        if let Some(pending_assets) = self.pending_assets.get(&token_id) {
+            if pending_assets.len() >= MAX_PENDING_ASSETS {
                 return Err(RMRKError::MaxPendingAssetsReached);
             }
             if pending_assets.iter().any(|&id| id == asset_id) {
                 return Err(RMRKError::AssetAlreadyExists);
             }
-            pending_assets.push(asset_id);
         }
+
+        // the parent collection updates its equipments before the asset is proposed,
+        // so the asset isn't left pending if the parent fails
+        if auto_accept {
+            self.replace_parent_equipments(
+                tx_manager,
+                token_id,
+                asset_id,
+                replaces_asset_with_id,
+                parent_id,
+            )?;
+        }
+
+        self.pending_assets
+            .entry(token_id)
+            .or_default()
+            .push(asset_id);
+
         if replaces_asset_with_id != 0 {
             self.asset_replacement
                 .entry(token_id)
@@ -89,46 +349,115 @@ impl Assets {
                 })
                 .or_insert_with(|| HashMap::from([(asset_id, replaces_asset_with_id)]));
         }
+
+        if auto_accept {
+            return self.accept_asset(tx_manager, token_id, asset_id, parent_id);
+        }
         Ok(RMRKReply::AssetAddedToToken)
     }
 
+    /// Sets the account whose proposed assets are accepted automatically for the token.
+    /// `None` removes the trusted issuer.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `issuer`: the trusted account.
+    ///
+    /// On success replies [`RMRKReply::TrustedIssuerSet`].
+    pub fn set_trusted_issuer(
+        &mut self,
+        token_id: TokenId,
+        issuer: Option<ActorId>,
+    ) -> Result<RMRKReply, RMRKError> {
+        if let Some(issuer) = issuer {
+            if issuer == ActorId::zero() {
+                return Err(RMRKError::ZeroIdForbidden);
+            }
+            self.trusted_issuers.insert(token_id, issuer);
+        } else {
+            self.trusted_issuers.remove(&token_id);
+        }
+        Ok(RMRKReply::TrustedIssuerSet)
+    }
+
+    pub fn is_trusted_issuer(&self, token_id: TokenId, account: &ActorId) -> bool {
+        self.trusted_issuers.get(&token_id) == Some(account)
+    }
+
+    /// Accepts an asset from the pending array of the token.
+    /// If the asset was proposed as a replacement of an active asset,
+    /// it takes the place of the replaced asset, otherwise it is added to the end of the active array.
+    /// The equipments of the replaced asset are moved to the new asset if it still fits the slot,
+    /// otherwise they are removed.
+    /// If the token is nested into a token of another collection,
+    /// the parent collection is asked to update the equipments holding the replaced asset
+    /// before the asset is accepted.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The asset must exist in the pending array of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `asset_id`: ID of the accepted asset.
+    /// * `parent_id`: the parent collection if the token is nested into a token of another collection.
+    ///
+    /// On success replies [`RMRKReply::AssetAccepted`] or [`RMRKReply::AssetReplaced`].
     pub fn accept_asset(
         &mut self,
+        tx_manager: &mut TxManager,
         token_id: TokenId,
         asset_id: u64,
+        parent_id: Option<ActorId>,
     ) -> Result<RMRKReply, RMRKError> {
-        if let Some(pending_assets) = self.pending_assets.get_mut(&token_id) {
-            if let Some(index) = pending_assets.iter().position(|&id| id == asset_id) {
-                pending_assets.remove(index);
-            } else {
-                return Err(RMRKError::AssetDoesNotExistInPendingArray);
-            };
+        let is_pending = self
+            .pending_assets
+            .get(&token_id)
+            .map(|assets| assets.contains(&asset_id))
+            .unwrap_or_default();
+        if !is_pending {
+            return Err(RMRKError::AssetDoesNotExistInPendingArray);
         }
 
-        let replace_id = if let Some(replacements) = self.asset_replacement.get(&token_id) {
-            if let Some(replace_id) = replacements.get(&asset_id) {
-                *replace_id
-            } else {
-                0
-            }
-        } else {
-            0
-        };
+        let replace_id = self
+            .asset_replacement
+            .get(&token_id)
+            .and_then(|replacements| replacements.get(&asset_id))
+            .copied()
+            .unwrap_or_default();
 
-        let replace_position = if replace_id != 0 {
-            if let Some(active_assets) = self.active_assets.get(&token_id) {
-                active_assets.iter().position(|&id| id == replace_id)
-            } else {
-                None
+        let replace_position = self.active_position(token_id, replace_id);
+        let (parent_migrated, parent_unequipped) =
+            self.replace_parent_equipments(tx_manager, token_id, asset_id, replace_id, parent_id)?;
+
+        self.remove_from_pending(token_id, asset_id)?;
+        if let Some(replacements) = self.asset_replacement.get_mut(&token_id) {
+            replacements.remove(&asset_id);
+            if replacements.is_empty() {
+                self.asset_replacement.remove(&token_id);
             }
-        } else {
-            None
-        };
+        }
 
         if let Some(replace_index) = replace_position {
+            // the new asset takes the position of the replaced one
+            // and therefore keeps its priority in `active_assets_priorities`
             self.active_assets.entry(token_id).and_modify(|assets| {
                 assets[replace_index] = asset_id;
             });
+            let (mut migrated_equipments, mut unequipped_equipments) =
+                self.replace_equipments(token_id, replace_id, asset_id);
+            migrated_equipments.extend(parent_migrated);
+            unequipped_equipments.extend(parent_unequipped);
+            migrated_equipments.sort();
+            unequipped_equipments.sort();
+            return Ok(RMRKReply::AssetReplaced {
+                replaced_asset_id: replace_id,
+                migrated_equipments,
+                unequipped_equipments,
+            });
         } else {
             let active_assets = self
                 .active_assets
@@ -145,6 +474,193 @@ impl Assets {
         Ok(RMRKReply::AssetAccepted)
     }
 
+    fn active_position(&self, token_id: TokenId, asset_id: u64) -> Option<usize> {
+        if asset_id == 0 {
+            return None;
+        }
+        self.active_assets
+            .get(&token_id)
+            .and_then(|assets| assets.iter().position(|&id| id == asset_id))
+    }
+
+    /// Asks the parent collection to update the equipments holding the replaced asset
+    /// if the token is nested into a token of another collection.
+    ///
+    /// Returns the migrated and the removed equipments of the parent collection.
+    fn replace_parent_equipments(
+        &self,
+        tx_manager: &mut TxManager,
+        token_id: TokenId,
+        asset_id: u64,
+        replace_id: u64,
+        parent_id: Option<ActorId>,
+    ) -> Result<(Vec<EquipmentSlot>, Vec<EquipmentSlot>), RMRKError> {
+        match (tx_manager.get_state(msg::id()), parent_id) {
+            (TxState::ReplyOnChildAssetReplaced, _) => {
+                tx_manager.get_decoded_data::<(Vec<EquipmentSlot>, Vec<EquipmentSlot>)>()
+            }
+            (TxState::MsgReplaceChildAssetSent, _) => Err(RMRKError::ParentDidNotReply),
+            (TxState::Error(error), _) => Err(error),
+            (_, Some(parent_id)) if self.active_position(token_id, replace_id).is_some() => {
+                let slot_part_id = self.get_equippable_slot_id(asset_id, parent_id).ok();
+                let msg_id = replace_child_asset_msg(
+                    &parent_id,
+                    token_id,
+                    replace_id,
+                    asset_id,
+                    slot_part_id,
+                );
+                tx_manager.set_tx_state(TxState::MsgReplaceChildAssetSent, msg_id);
+                exec::wait_for(5);
+            }
+            _ => Ok((Vec::new(), Vec::new())),
+        }
+    }
+
+    /// Updates the equipments of this collection that refer to the replaced asset of the token,
+    /// both as the parent asset and as the child asset.
+    /// The equipment is moved to the new asset if the new asset still fits the slot,
+    /// otherwise the equipment is removed.
+    /// The equipments of the parent collection are updated through [`RMRKAction::ReplaceChildAsset`].
+    ///
+    /// Returns the migrated and the removed equipments as `(token_id, catalog_address, slot_part_id)`.
+    fn replace_equipments(
+        &mut self,
+        token_id: TokenId,
+        old_asset_id: u64,
+        new_asset_id: u64,
+    ) -> (Vec<EquipmentSlot>, Vec<EquipmentSlot>) {
+        let program_id = exec::program_id();
+        let new_catalog_address = self.catalog_addresses.get(&new_asset_id).copied();
+        let new_part_ids = self
+            .part_ids
+            .get(&new_asset_id)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut migrated = Vec::new();
+        let mut unequipped = Vec::new();
+        for ((parent_token_id, catalog_address), slots) in self.equipments.iter_mut() {
+            if *parent_token_id != token_id {
+                continue;
+            }
+            slots.retain(|slot_part_id, equipment| {
+                if equipment.asset_id != old_asset_id {
+                    return true;
+                }
+                let slot = (*parent_token_id, *catalog_address, *slot_part_id);
+                if new_catalog_address == Some(*catalog_address)
+                    && new_part_ids.contains(slot_part_id)
+                {
+                    equipment.asset_id = new_asset_id;
+                    migrated.push(slot);
+                    return true;
+                }
+                unequipped.push(slot);
+                false
+            });
+        }
+        self.equipments.retain(|_, slots| !slots.is_empty());
+
+        // the token may be equipped into a token of the same collection
+        let new_slot_part_id = self.get_equippable_slot_id(new_asset_id, program_id).ok();
+        let (child_migrated, child_unequipped) = self.replace_child_asset_equipments(
+            program_id,
+            token_id,
+            old_asset_id,
+            new_asset_id,
+            new_slot_part_id,
+        );
+        migrated.extend(child_migrated);
+        unequipped.extend(child_unequipped);
+
+        migrated.sort();
+        unequipped.sort();
+        (migrated, unequipped)
+    }
+
+    /// Updates the equipments holding the replaced asset of the child token.
+    /// The equipment is moved to the new asset if it can be equipped into the same slot,
+    /// otherwise the equipment is removed.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the child collection.
+    ///
+    /// # Arguments:
+    /// * `child_token_id`: the tokenId of the child NFT.
+    /// * `old_asset_id`: ID of the replaced child asset.
+    /// * `new_asset_id`: ID of the new child asset.
+    /// * `slot_part_id`: the slot the new asset can be equipped into.
+    ///
+    /// On success replies [`RMRKReply::ChildAssetReplaced`].
+    pub fn replace_child_asset(
+        &mut self,
+        child_token_id: TokenId,
+        old_asset_id: u64,
+        new_asset_id: u64,
+        slot_part_id: Option<PartId>,
+    ) -> Result<RMRKReply, RMRKError> {
+        let (mut migrated_equipments, mut unequipped_equipments) = self
+            .replace_child_asset_equipments(
+                msg::source(),
+                child_token_id,
+                old_asset_id,
+                new_asset_id,
+                slot_part_id,
+            );
+        migrated_equipments.sort();
+        unequipped_equipments.sort();
+        Ok(RMRKReply::ChildAssetReplaced {
+            migrated_equipments,
+            unequipped_equipments,
+        })
+    }
+
+    fn replace_child_asset_equipments(
+        &mut self,
+        child_id: ActorId,
+        child_token_id: TokenId,
+        old_asset_id: u64,
+        new_asset_id: u64,
+        slot_part_id: Option<PartId>,
+    ) -> (Vec<EquipmentSlot>, Vec<EquipmentSlot>) {
+        let mut migrated = Vec::new();
+        let mut unequipped = Vec::new();
+        for ((parent_token_id, catalog_address), slots) in self.equipments.iter_mut() {
+            slots.retain(|equipped_slot_part_id, equipment| {
+                if equipment.child_id != child_id
+                    || equipment.child_token_id != child_token_id
+                    || equipment.child_asset_id != old_asset_id
+                {
+                    return true;
+                }
+                let slot = (*parent_token_id, *catalog_address, *equipped_slot_part_id);
+                if slot_part_id == Some(*equipped_slot_part_id) {
+                    equipment.child_asset_id = new_asset_id;
+                    migrated.push(slot);
+                    return true;
+                }
+                unequipped.push(slot);
+                false
+            });
+        }
+        self.equipments.retain(|_, slots| !slots.is_empty());
+        (migrated, unequipped)
+    }
+
+    fn remove_from_pending(&mut self, token_id: TokenId, asset_id: u64) -> Result<(), RMRKError> {
+        if let Some(pending_assets) = self.pending_assets.get_mut(&token_id) {
+            if let Some(index) = pending_assets.iter().position(|&id| id == asset_id) {
+                pending_assets.remove(index);
+                if pending_assets.is_empty() {
+                    self.pending_assets.remove(&token_id);
+                }
+                return Ok(());
+            }
+        }
+        Err(RMRKError::AssetDoesNotExistInPendingArray)
+    }
+
     pub fn set_valid_parent_for_equippable_group(
         &mut self,
         equippable_group_id: u64,
@@ -203,6 +719,90 @@ impl Assets {
         Err(RMRKError::ActiveAssetNotFound)
     }
 
+    /// Gathers the data required to render the composed NFT.
+    /// The parts of the asset are requested from the catalog with a single message.
+    ///
+    /// # Requirements:
+    /// * The asset with indicated `asset_id` must be active for the token.
+    /// * The asset must have a catalog address.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `asset_id`: ID of the composed asset.
+    ///
+    /// On success replies [`RMRKReply::ComposedEquippables`].
+    pub fn compose_equippables(
+        &self,
+        tx_manager: &mut TxManager,
+        token_id: TokenId,
+        asset_id: u64,
+    ) -> Result<RMRKReply, RMRKError> {
+        self.in_active_assets(token_id, asset_id)?;
+        let catalog_address = if let Some(id) = self.catalog_addresses.get(&asset_id) {
+            id
+        } else {
+            return Err(RMRKError::CatalogDoesNotExist);
+        };
+        let part_ids = self.part_ids.get(&asset_id).cloned().unwrap_or_default();
+        let parts = match tx_manager.get_state(msg::id()) {
+            TxState::Initial if part_ids.is_empty() => Vec::new(),
+            TxState::Initial => {
+                let msg_id = check_parts_msg(catalog_address, part_ids.clone());
+                tx_manager.set_tx_state(TxState::MsgCheckPartsSent, msg_id);
+                exec::wait_for(5);
+            }
+            TxState::ReplyCheckPartsReceived => tx_manager.get_decoded_data::<Vec<Part>>()?,
+            // the message was woken up by timeout, so the catalog did not reply
+            TxState::MsgCheckPartsSent => return Err(RMRKError::ErrorInCatalog),
+            TxState::Error(error) => return Err(error),
+            _ => {
+                unreachable!()
+            }
+        };
+
+        let equipments = self.equipments.get(&(token_id, *catalog_address));
+        let mut fixed_parts = Vec::new();
+        let mut slot_parts = Vec::new();
+        let mut other_parts = Vec::new();
+        for (part_id, part) in part_ids.into_iter().zip(parts) {
+            match part {
+                Part::Fixed(FixedPart { z, metadata_uri }) => fixed_parts.push(FixedPartData {
+                    part_id,
+                    z,
+                    metadata_uri,
+                }),
+                Part::Slot(SlotPart {
+                    z, metadata_uri, ..
+                }) => {
+                    let equipment = equipments
+                        .and_then(|equipments| equipments.get(&part_id))
+                        .filter(|equipment| equipment.asset_id == asset_id)
+                        .cloned();
+                    slot_parts.push(EquippedSlotPart {
+                        part_id,
+                        z,
+                        metadata_uri,
+                        equipment,
+                    })
+                }
+                part => other_parts.push((part_id, part)),
+            }
+        }
+
+        Ok(RMRKReply::ComposedEquippables(ComposedEquippables {
+            metadata_uri: self.assets.get(&asset_id).cloned().unwrap_or_default(),
+            equippable_group_id: self
+                .equippable_group_ids
+                .get(&asset_id)
+                .copied()
+                .unwrap_or_default(),
+            catalog_address: *catalog_address,
+            fixed_parts,
+            slot_parts,
+            other_parts,
+        }))
+    }
+
     /// * `token_id`: ID of the token that had an asset equipped
     /// * `child_token_id`: ID of the child token we are equipping into the slot
     /// * `child_id`: Address of the child token's collection
@@ -235,13 +835,25 @@ impl Assets {
                     }
                 }
 
+                // a fixed part can not receive a child
+                if let Some(fixed_part_ids) = self.fixed_part_ids.get(&asset_id) {
+                    if fixed_part_ids.contains(&slot_part_id) {
+                        return Err(RMRKError::WrongPartFormat);
+                    }
+                }
+
                 // check if a given asset accepts a given slot or not.
                 if let Some(part_ids) = self.part_ids.get(&asset_id) {
                     if part_ids.iter().any(|&part_id| part_id == slot_part_id) {
                         // Check from catalog perspective:
                         // - the indicated part has the Slot type;
-                        // - this NFT contract is in equiappable list
-                        let msg_id = check_equippable_msg(catalog_address, slot_part_id, child_id);
+                        // - this NFT contract or the child token is in equiappable list
+                        let msg_id = check_equippable_msg(
+                            catalog_address,
+                            slot_part_id,
+                            child_id,
+                            child_token_id,
+                        );
                         tx_manager.set_tx_state(TxState::MsgCheckEquippableSent, msg_id);
                         exec::wait_for(5);
                     }
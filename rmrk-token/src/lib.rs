@@ -2,16 +2,18 @@
 
 use catalog_io::{CatalogError, CatalogReply};
 use equippable::Assets;
-use gstd::{exec, msg, prelude::*, ActorId, MessageId};
+use gstd::{exec, msg, prelude::*, prog::ProgramGenerator, ActorId, MessageId};
 
 use primitive_types::U256;
+use resource_io::{InitResource, ResourceError, ResourceEvent};
 use rmrk_io::*;
-use types::primitives::{CollectionAndToken, PartId, TokenId};
+use types::primitives::{CollectionAndToken, PartId, ResourceId, TokenId};
 mod burn;
 mod checks;
 mod children;
 mod equippable;
 mod messages;
+mod resources;
 mod transfer;
 use messages::*;
 mod mint;
@@ -32,6 +34,11 @@ struct RMRKToken {
     accepted_children: HashMap<TokenId, HashSet<CollectionAndToken>>,
     children_status: HashMap<CollectionAndToken, ChildStatus>,
     balances: HashMap<ActorId, U256>,
+    resource_storage_id: ActorId,
+    pending_resources: HashMap<TokenId, HashSet<ResourceId>>,
+    resource_overwrites: HashMap<TokenId, HashMap<ResourceId, ResourceId>>,
+    active_resources: HashMap<TokenId, Vec<ResourceId>>,
+    active_resources_priorities: HashMap<TokenId, Vec<u8>>,
 }
 
 static mut RMRK: Option<RMRKToken> = None;
@@ -58,10 +65,22 @@ pub enum TxState {
     ReplyOnAddResourceReceived,
     MsgGetResourceSent,
     ReplyOnGetResourceReceived,
+    MsgAddTokenReferenceSent,
+    ReplyOnTokenReferenceAdded,
+    MsgRemoveTokenReferenceSent,
+    ReplyOnTokenReferenceRemoved,
     MsgCheckEquippableSent,
     ReplyCheckEquippableReceived,
     MsgCanTokenBeEquippedSent,
     ReplyCanTokenBeEquippedReceived,
+    MsgCheckPartsSent,
+    ReplyCheckPartsReceived,
+    MsgRegisterDependentSent,
+    ReplyDependentRegistered,
+    MsgUnregisterDependentSent,
+    ReplyDependentUnregistered,
+    MsgReplaceChildAssetSent,
+    ReplyOnChildAssetReplaced,
     Completed,
     Error(RMRKError),
     CheckMsgSourceAccount(ActorId),
@@ -156,10 +175,32 @@ impl RMRKToken {
 extern "C" fn init() {
     let config: InitRMRK = msg::load().expect("Unable to decode InitRMRK");
     let tx_manager: TxManager = Default::default();
+    // the init fails before creating any program if the storage is both created and attached
+    if config.resource_hash.is_some() && config.resource_address.is_some() {
+        panic!("RMRK: `resource_hash` and `resource_address` can not be set together");
+    }
+    let resource_storage_id = match (config.resource_hash, config.resource_address) {
+        (Some(resource_hash), _) => {
+            let (_, resource_storage_id) = ProgramGenerator::create_program(
+                resource_hash.into(),
+                InitResource {
+                    resource_name: config.resource_name,
+                    // the issuer manages the resources directly in the storage
+                    owner: Some(msg::source()),
+                },
+                0,
+            )
+            .expect("Error in creating the resource storage program");
+            resource_storage_id
+        }
+        (None, Some(resource_address)) => resource_address,
+        (None, None) => ActorId::zero(),
+    };
     let rmrk = RMRKToken {
         name: config.name,
         symbol: config.symbol,
         admin: msg::source(),
+        resource_storage_id,
         ..RMRKToken::default()
     };
     let assets: Assets = Default::default();
@@ -209,7 +250,9 @@ fn process_reply(
                 check_approved_account(tx_manager, rmrk, token_id)?;
             }
             tx_manager.check_for_error()?;
-            rmrk.transfer(tx_manager, &to, token_id)
+            let reply = rmrk.transfer(tx_manager, &to, token_id)?;
+            assets.trusted_issuers.remove(&token_id);
+            Ok(reply)
         }
         RMRKAction::TransferToNft {
             to,
@@ -221,7 +264,9 @@ fn process_reply(
                 check_approved_account(tx_manager, rmrk, token_id)?;
             }
             tx_manager.check_for_error()?;
-            rmrk.transfer_to_nft(tx_manager, &to, destination_id, token_id)
+            let reply = rmrk.transfer_to_nft(tx_manager, &to, destination_id, token_id)?;
+            assets.trusted_issuers.remove(&token_id);
+            Ok(reply)
         }
         RMRKAction::Approve { to, token_id } => {
             if tx_manager.tx_does_not_exist() {
@@ -296,7 +341,9 @@ fn process_reply(
             if tx_manager.tx_does_not_exist() {
                 tx_manager.set_tx(action);
             }
-            rmrk.burn_from_parent(tx_manager, child_token_id)
+            let reply = rmrk.burn_from_parent(tx_manager, child_token_id)?;
+            assets.trusted_issuers.remove(&child_token_id);
+            Ok(reply)
         }
         RMRKAction::Burn(token_id) => {
             if tx_manager.tx_does_not_exist() {
@@ -304,7 +351,9 @@ fn process_reply(
                 check_approved_account(tx_manager, rmrk, token_id)?;
             }
             tx_manager.check_for_error()?;
-            rmrk.burn(tx_manager, token_id)
+            let reply = rmrk.burn(tx_manager, token_id)?;
+            assets.trusted_issuers.remove(&token_id);
+            Ok(reply)
         }
         RMRKAction::RootOwner(token_id) => {
             if tx_manager.tx_does_not_exist() {
@@ -339,22 +388,95 @@ fn process_reply(
         }
 
         RMRKAction::AddEquippableAssetEntry {
+            asset_id,
             equippable_group_id,
             catalog_address,
             metadata_uri,
             part_ids,
-        } => assets.add_equippable_asset_entry(
-            equippable_group_id,
-            catalog_address,
+            metadata_hash,
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+            }
+            tx_manager.check_for_error()?;
+            rmrk.check_admin()?;
+            assets.add_equippable_asset_entry(
+                tx_manager,
+                asset_id,
+                equippable_group_id,
+                catalog_address,
+                metadata_uri,
+                part_ids,
+                metadata_hash,
+            )
+        }
+        RMRKAction::UpdateAssetEntry {
+            asset_id,
             metadata_uri,
+            metadata_hash,
             part_ids,
-        ),
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+            }
+            tx_manager.check_for_error()?;
+            rmrk.check_admin()?;
+            assets.update_asset_entry(tx_manager, asset_id, metadata_uri, metadata_hash, part_ids)
+        }
         RMRKAction::AddAssetToToken {
             token_id,
             asset_id,
             replaces_asset_with_id,
-        } => assets.add_asset_to_token(token_id, asset_id, replaces_asset_with_id),
-        RMRKAction::AcceptAsset { token_id, asset_id } => assets.accept_asset(token_id, asset_id),
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+            }
+            tx_manager.check_for_error()?;
+            let parent_id = rmrk.get_parent_collection(token_id)?;
+            // the asset is accepted immediately and waits for the parent collection
+            // to update the equipments of the replaced asset
+            if matches!(
+                tx_manager.get_state(msg::id()),
+                TxState::MsgReplaceChildAssetSent | TxState::ReplyOnChildAssetReplaced
+            ) {
+                return assets.add_asset_to_token(
+                    tx_manager,
+                    token_id,
+                    asset_id,
+                    replaces_asset_with_id,
+                    true,
+                    parent_id,
+                );
+            }
+            // the asset is accepted immediately if it is proposed by the root owner or the trusted issuer
+            let auto_accept = assets.is_trusted_issuer(token_id, &msg::source())
+                || rmrk.get_root_owner(tx_manager, token_id)? == msg::source();
+            assets.add_asset_to_token(
+                tx_manager,
+                token_id,
+                asset_id,
+                replaces_asset_with_id,
+                auto_accept,
+                parent_id,
+            )
+        }
+        RMRKAction::SetTrustedIssuer { token_id, issuer } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+                check_root_owner(tx_manager, rmrk, token_id)?;
+            }
+            tx_manager.check_for_error()?;
+            assets.set_trusted_issuer(token_id, issuer)
+        }
+        RMRKAction::AcceptAsset { token_id, asset_id } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+                check_approved_account(tx_manager, rmrk, token_id)?;
+            }
+            tx_manager.check_for_error()?;
+            let parent_id = rmrk.get_parent_collection(token_id)?;
+            assets.accept_asset(tx_manager, token_id, asset_id, parent_id)
+        }
         RMRKAction::SetValidParentForEquippableGroup {
             equippable_group_id,
             slot_part_id,
@@ -375,6 +497,76 @@ fn process_reply(
             asset_id,
             slot_part_id,
         ),
+        RMRKAction::ReplaceChildAsset {
+            child_token_id,
+            old_asset_id,
+            new_asset_id,
+            slot_part_id,
+        } => assets.replace_child_asset(child_token_id, old_asset_id, new_asset_id, slot_part_id),
+        RMRKAction::ComposeEquippables { token_id, asset_id } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+            }
+            tx_manager.check_for_error()?;
+            assets.compose_equippables(tx_manager, token_id, asset_id)
+        }
+        RMRKAction::AddResourceEntry {
+            resource_id,
+            resource,
+            metadata_hash,
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+            }
+            tx_manager.check_for_error()?;
+            rmrk.check_admin()?;
+            rmrk.add_resource_entry(tx_manager, resource_id, resource, metadata_hash)
+        }
+        RMRKAction::AddResourceToToken {
+            token_id,
+            resource_id,
+            overwrite_id,
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+            }
+            tx_manager.check_for_error()?;
+            rmrk.check_admin()?;
+            rmrk.add_resource_to_token(tx_manager, token_id, resource_id, overwrite_id)
+        }
+        RMRKAction::AcceptResource {
+            token_id,
+            resource_id,
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+                check_approved_account(tx_manager, rmrk, token_id)?;
+            }
+            tx_manager.check_for_error()?;
+            rmrk.accept_resource(tx_manager, token_id, resource_id)
+        }
+        RMRKAction::RejectResource {
+            token_id,
+            resource_id,
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+                check_approved_account(tx_manager, rmrk, token_id)?;
+            }
+            tx_manager.check_for_error()?;
+            rmrk.reject_resource(tx_manager, token_id, resource_id)
+        }
+        RMRKAction::SetResourcePriority {
+            token_id,
+            priorities,
+        } => {
+            if tx_manager.tx_does_not_exist() {
+                tx_manager.set_tx(action);
+                check_approved_account(tx_manager, rmrk, token_id)?;
+            }
+            tx_manager.check_for_error()?;
+            rmrk.set_resource_priority(token_id, priorities)
+        }
     }
 }
 #[no_mangle]
@@ -441,6 +633,13 @@ extern "C" fn handle_reply() {
             processing_msg_id,
         ),
         TxState::MsgGetRootOwnerSent => get_root_owner(tx, processing_msg_id),
+        TxState::MsgCheckPartsSent => get_parts(tx, processing_msg_id),
+        TxState::MsgAddResourceSent => add_resource_reply(tx, processing_msg_id),
+        TxState::MsgAddTokenReferenceSent => add_token_reference_reply(tx, processing_msg_id),
+        TxState::MsgRemoveTokenReferenceSent => remove_token_reference_reply(tx, processing_msg_id),
+        TxState::MsgRegisterDependentSent => register_dependent_reply(tx, processing_msg_id),
+        TxState::MsgUnregisterDependentSent => unregister_dependent_reply(tx, processing_msg_id),
+        TxState::MsgReplaceChildAssetSent => replace_child_asset_reply(tx, processing_msg_id),
         _ => {}
     }
 }
@@ -592,3 +791,129 @@ fn get_root_owner(tx: &mut Tx, processing_msg_id: MessageId) {
     }
     exec::wake(processing_msg_id).expect("Failed to wake the message");
 }
+
+fn get_parts(tx: &mut Tx, processing_msg_id: MessageId) {
+    let reply: Result<CatalogReply, CatalogError> =
+        msg::load().expect("Failed to decode the reply");
+    match reply {
+        Ok(CatalogReply::Parts(parts)) => {
+            tx.data = Some(parts.encode());
+            tx.state = TxState::ReplyCheckPartsReceived;
+        }
+        Ok(_) => {
+            tx.state = TxState::Error(RMRKError::UnexpectedReply);
+        }
+        Err(CatalogError::PartDoesNotExist) => {
+            tx.state = TxState::Error(RMRKError::PartDoesNotExistInCatalog);
+        }
+        Err(_) => {
+            tx.state = TxState::Error(RMRKError::ErrorInCatalog);
+        }
+    }
+    exec::wake(processing_msg_id).expect("Failed to wake the message");
+}
+
+fn register_dependent_reply(tx: &mut Tx, processing_msg_id: MessageId) {
+    let reply: Result<CatalogReply, CatalogError> =
+        msg::load().expect("Failed to decode the reply");
+    match reply {
+        Ok(CatalogReply::DependentRegistered(_)) => {
+            tx.state = TxState::ReplyDependentRegistered;
+        }
+        Ok(_) => {
+            tx.state = TxState::Error(RMRKError::UnexpectedReply);
+        }
+        Err(_) => {
+            tx.state = TxState::Error(RMRKError::ErrorInCatalog);
+        }
+    }
+    exec::wake(processing_msg_id).expect("Failed to wake the message");
+}
+
+fn unregister_dependent_reply(tx: &mut Tx, processing_msg_id: MessageId) {
+    let reply: Result<CatalogReply, CatalogError> =
+        msg::load().expect("Failed to decode the reply");
+    match reply {
+        Ok(CatalogReply::DependentUnregistered(_)) => {
+            tx.state = TxState::ReplyDependentUnregistered;
+        }
+        Ok(_) => {
+            tx.state = TxState::Error(RMRKError::UnexpectedReply);
+        }
+        Err(_) => {
+            tx.state = TxState::Error(RMRKError::ErrorInCatalog);
+        }
+    }
+    exec::wake(processing_msg_id).expect("Failed to wake the message");
+}
+
+fn replace_child_asset_reply(tx: &mut Tx, processing_msg_id: MessageId) {
+    let reply: Result<RMRKReply, RMRKError> = msg::load().expect("Failed to decode the reply");
+    match reply {
+        Ok(RMRKReply::ChildAssetReplaced {
+            migrated_equipments,
+            unequipped_equipments,
+        }) => {
+            tx.data = Some((migrated_equipments, unequipped_equipments).encode());
+            tx.state = TxState::ReplyOnChildAssetReplaced;
+        }
+        Ok(_) => {
+            tx.state = TxState::Error(RMRKError::UnexpectedReply);
+        }
+        Err(error) => {
+            tx.state = TxState::Error(error);
+        }
+    }
+    exec::wake(processing_msg_id).expect("Failed to wake the message");
+}
+
+fn add_resource_reply(tx: &mut Tx, processing_msg_id: MessageId) {
+    let reply: Result<ResourceEvent, ResourceError> =
+        msg::load().expect("Failed to decode the reply");
+    match reply {
+        Ok(ResourceEvent::ResourceEntryAdded { .. }) => {
+            tx.state = TxState::ReplyOnAddResourceReceived;
+        }
+        Ok(_) => {
+            tx.state = TxState::Error(RMRKError::UnexpectedReply);
+        }
+        Err(error) => {
+            tx.state = TxState::Error(error.into());
+        }
+    }
+    exec::wake(processing_msg_id).expect("Failed to wake the message");
+}
+
+fn add_token_reference_reply(tx: &mut Tx, processing_msg_id: MessageId) {
+    let reply: Result<ResourceEvent, ResourceError> =
+        msg::load().expect("Failed to decode the reply");
+    match reply {
+        Ok(ResourceEvent::TokenReferenceAdded { .. }) => {
+            tx.state = TxState::ReplyOnTokenReferenceAdded;
+        }
+        Ok(_) => {
+            tx.state = TxState::Error(RMRKError::UnexpectedReply);
+        }
+        Err(error) => {
+            tx.state = TxState::Error(error.into());
+        }
+    }
+    exec::wake(processing_msg_id).expect("Failed to wake the message");
+}
+
+fn remove_token_reference_reply(tx: &mut Tx, processing_msg_id: MessageId) {
+    let reply: Result<ResourceEvent, ResourceError> =
+        msg::load().expect("Failed to decode the reply");
+    match reply {
+        Ok(ResourceEvent::TokenReferenceRemoved { .. }) => {
+            tx.state = TxState::ReplyOnTokenReferenceRemoved;
+        }
+        Ok(_) => {
+            tx.state = TxState::Error(RMRKError::UnexpectedReply);
+        }
+        Err(error) => {
+            tx.state = TxState::Error(error.into());
+        }
+    }
+    exec::wake(processing_msg_id).expect("Failed to wake the message");
+}
@@ -1,7 +1,8 @@
 use crate::*;
 use catalog_io::*;
 use gstd::{exec, msg, ActorId};
-use types::primitives::{CollectionId, PartId, TokenId};
+use resource_io::{Resource, ResourceAction};
+use types::primitives::{CollectionId, PartId, ResourceId, TokenId};
 pub const REPLY_PROVISION: u64 = 1_000_000_000;
 
 pub fn add_child_msg(
@@ -81,12 +82,14 @@ pub fn check_equippable_msg(
     catalog_id: &ActorId,
     part_id: PartId,
     collection_id: &CollectionId,
+    token_id: TokenId,
 ) -> MessageId {
     let msg_id = msg::send(
         *catalog_id,
         CatalogAction::CheckEquippable {
             part_id,
             collection_id: *collection_id,
+            token_id: Some(token_id),
         },
         0,
     )
@@ -95,6 +98,83 @@ pub fn check_equippable_msg(
     msg_id
 }
 
+pub fn check_parts_msg(catalog_id: &ActorId, part_ids: Vec<PartId>) -> MessageId {
+    let msg_id = msg::send(*catalog_id, CatalogAction::CheckParts(part_ids), 0)
+        .expect("Error in sending message [CatalogAction::CheckParts]");
+    exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
+    msg_id
+}
+
+pub fn register_dependent_msg(catalog_id: &ActorId, part_ids: Vec<PartId>) -> MessageId {
+    let msg_id = msg::send(*catalog_id, CatalogAction::RegisterDependent(part_ids), 0)
+        .expect("Error in sending message [CatalogAction::RegisterDependent]");
+    exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
+    msg_id
+}
+
+pub fn unregister_dependent_msg(catalog_id: &ActorId, part_ids: Vec<PartId>) -> MessageId {
+    let msg_id = msg::send(*catalog_id, CatalogAction::UnregisterDependent(part_ids), 0)
+        .expect("Error in sending message [CatalogAction::UnregisterDependent]");
+    exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
+    msg_id
+}
+
+pub fn add_resource_entry_msg(
+    resource_storage_id: &ActorId,
+    resource_id: ResourceId,
+    resource: Resource,
+    metadata_hash: Option<[u8; 32]>,
+) -> MessageId {
+    let msg_id = msg::send(
+        *resource_storage_id,
+        ResourceAction::AddResourceEntry {
+            resource_id,
+            resource,
+            metadata_hash,
+        },
+        0,
+    )
+    .expect("Error in sending message [ResourceAction::AddResourceEntry]");
+    exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
+    msg_id
+}
+
+pub fn add_token_reference_msg(
+    resource_storage_id: &ActorId,
+    resource_id: ResourceId,
+    token_id: TokenId,
+) -> MessageId {
+    let msg_id = msg::send(
+        *resource_storage_id,
+        ResourceAction::AddTokenReference {
+            resource_id,
+            token_id,
+        },
+        0,
+    )
+    .expect("Error in sending message [ResourceAction::AddTokenReference]");
+    exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
+    msg_id
+}
+
+pub fn remove_token_reference_msg(
+    resource_storage_id: &ActorId,
+    resource_id: ResourceId,
+    token_id: TokenId,
+) -> MessageId {
+    let msg_id = msg::send(
+        *resource_storage_id,
+        ResourceAction::RemoveTokenReference {
+            resource_id,
+            token_id,
+        },
+        0,
+    )
+    .expect("Error in sending message [ResourceAction::RemoveTokenReference]");
+    exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
+    msg_id
+}
+
 pub fn can_token_be_equipped_msg(
     child_id: &ActorId,
     parent_id: &ActorId,
@@ -116,3 +196,25 @@ pub fn can_token_be_equipped_msg(
     exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
     msg_id
 }
+
+pub fn replace_child_asset_msg(
+    parent_id: &ActorId,
+    child_token_id: TokenId,
+    old_asset_id: u64,
+    new_asset_id: u64,
+    slot_part_id: Option<PartId>,
+) -> MessageId {
+    let msg_id = msg::send(
+        *parent_id,
+        RMRKAction::ReplaceChildAsset {
+            child_token_id,
+            old_asset_id,
+            new_asset_id,
+            slot_part_id,
+        },
+        0,
+    )
+    .expect("Error in sending message [RMRKAction::ReplaceChildAsset]");
+    exec::reply_deposit(msg_id, REPLY_PROVISION).expect("Failed to create a reply provision");
+    msg_id
+}
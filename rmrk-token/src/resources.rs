@@ -0,0 +1,295 @@
+use crate::*;
+use gstd::msg;
+use resource_io::Resource;
+
+impl RMRKToken {
+    /// Adds resource entry to the resource storage contract of the collection.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * The resource storage must be created or attached on init.
+    ///
+    /// # Arguments:
+    /// * `resource_id`: is a resource identifier.
+    /// * `resource`: is a resource struct that can be `Basic`, `Slot` or `Composed`.
+    /// * `metadata_hash`: optional 32-byte hash of the content behind the metadata URI.
+    ///
+    /// On success replies [`RMRKReply::ResourceEntryAdded`].
+    pub fn add_resource_entry(
+        &self,
+        tx_manager: &mut TxManager,
+        resource_id: ResourceId,
+        resource: Resource,
+        metadata_hash: Option<[u8; 32]>,
+    ) -> Result<RMRKReply, RMRKError> {
+        let resource_storage_id = self.get_resource_storage_id()?;
+        let state = tx_manager.get_state(msg::id());
+        match state {
+            TxState::Initial => {
+                let msg_id = add_resource_entry_msg(
+                    &resource_storage_id,
+                    resource_id,
+                    resource,
+                    metadata_hash,
+                );
+                tx_manager.set_tx_state(TxState::MsgAddResourceSent, msg_id);
+                exec::wait_for(5);
+            }
+            TxState::ReplyOnAddResourceReceived => Ok(RMRKReply::ResourceEntryAdded(resource_id)),
+            // the message was woken up by timeout, so the resource storage did not reply
+            TxState::MsgAddResourceSent => Err(RMRKError::ErrorInResourceStorage),
+            TxState::Error(error) => Err(error),
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+
+    /// Adds the resource from the resource storage to the pending resources of the token.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the collection issuer.
+    /// * The token must exist.
+    /// * The resource must exist in the resource storage.
+    /// * The resource must be neither pending nor active for the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `resource_id`: is a resource identifier.
+    /// * `overwrite_id`: ID of the resource to be overwritten when the new resource is accepted (0 if none).
+    ///
+    /// On success replies [`RMRKReply::ResourceAddedToToken`].
+    pub fn add_resource_to_token(
+        &mut self,
+        tx_manager: &mut TxManager,
+        token_id: TokenId,
+        resource_id: ResourceId,
+        overwrite_id: ResourceId,
+    ) -> Result<RMRKReply, RMRKError> {
+        let resource_storage_id = self.get_resource_storage_id()?;
+        self.if_token_exists(token_id)?;
+        if let Some(resources) = self.pending_resources.get(&token_id) {
+            if resources.contains(&resource_id) {
+                return Err(RMRKError::ResourceAlreadyExists);
+            }
+        }
+        if let Some(resources) = self.active_resources.get(&token_id) {
+            if resources.contains(&resource_id) {
+                return Err(RMRKError::ResourceAlreadyExists);
+            }
+        }
+        let state = tx_manager.get_state(msg::id());
+        match state {
+            TxState::Initial => {
+                // the resource storage checks that the resource exists
+                // and refuses to remove it while the token references it
+                let msg_id = add_token_reference_msg(&resource_storage_id, resource_id, token_id);
+                tx_manager.set_tx_state(TxState::MsgAddTokenReferenceSent, msg_id);
+                exec::wait_for(5);
+            }
+            TxState::ReplyOnTokenReferenceAdded => {
+                self.pending_resources
+                    .entry(token_id)
+                    .or_default()
+                    .insert(resource_id);
+                if overwrite_id != 0 {
+                    self.resource_overwrites
+                        .entry(token_id)
+                        .or_default()
+                        .insert(resource_id, overwrite_id);
+                }
+                Ok(RMRKReply::ResourceAddedToToken)
+            }
+            // the message was woken up by timeout, so the resource storage did not reply
+            TxState::MsgAddTokenReferenceSent => Err(RMRKError::ErrorInResourceStorage),
+            TxState::Error(error) => Err(error),
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+
+    /// Accepts the resource from the pending resources of the token.
+    /// If the resource overwrites an active resource, it takes its position and priority,
+    /// and the overwritten resource is released in the resource storage.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The resource must exist in the pending array of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `resource_id`: ID of the accepted resource.
+    ///
+    /// On success replies [`RMRKReply::ResourceAccepted`].
+    pub fn accept_resource(
+        &mut self,
+        tx_manager: &mut TxManager,
+        token_id: TokenId,
+        resource_id: ResourceId,
+    ) -> Result<RMRKReply, RMRKError> {
+        self.check_pending_resource(token_id, resource_id)?;
+        let overwrite = self
+            .resource_overwrites
+            .get(&token_id)
+            .and_then(|overwrites| overwrites.get(&resource_id))
+            .and_then(|overwrite_id| {
+                self.active_resources
+                    .get(&token_id)
+                    .and_then(|resources| resources.iter().position(|id| id == overwrite_id))
+                    .map(|position| (*overwrite_id, position))
+            });
+        let state = tx_manager.get_state(msg::id());
+        match state {
+            TxState::MsgSourceAccountChecked => {
+                if let Some((overwrite_id, _)) = overwrite {
+                    let resource_storage_id = self.get_resource_storage_id()?;
+                    let msg_id =
+                        remove_token_reference_msg(&resource_storage_id, overwrite_id, token_id);
+                    tx_manager.set_tx_state(TxState::MsgRemoveTokenReferenceSent, msg_id);
+                    exec::wait_for(5);
+                } else {
+                    self.activate_resource(token_id, resource_id, None);
+                    Ok(RMRKReply::ResourceAccepted)
+                }
+            }
+            TxState::ReplyOnTokenReferenceRemoved => {
+                let position = overwrite.map(|(_, position)| position);
+                self.activate_resource(token_id, resource_id, position);
+                Ok(RMRKReply::ResourceAccepted)
+            }
+            // the message was woken up by timeout, so the resource storage did not reply
+            TxState::MsgRemoveTokenReferenceSent => Err(RMRKError::ErrorInResourceStorage),
+            TxState::Error(error) => Err(error),
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+
+    /// Rejects the resource from the pending resources of the token
+    /// and releases it in the resource storage.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The resource must exist in the pending array of the token.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `resource_id`: ID of the rejected resource.
+    ///
+    /// On success replies [`RMRKReply::ResourceRejected`].
+    pub fn reject_resource(
+        &mut self,
+        tx_manager: &mut TxManager,
+        token_id: TokenId,
+        resource_id: ResourceId,
+    ) -> Result<RMRKReply, RMRKError> {
+        self.check_pending_resource(token_id, resource_id)?;
+        let state = tx_manager.get_state(msg::id());
+        match state {
+            TxState::MsgSourceAccountChecked => {
+                let resource_storage_id = self.get_resource_storage_id()?;
+                let msg_id =
+                    remove_token_reference_msg(&resource_storage_id, resource_id, token_id);
+                tx_manager.set_tx_state(TxState::MsgRemoveTokenReferenceSent, msg_id);
+                exec::wait_for(5);
+            }
+            TxState::ReplyOnTokenReferenceRemoved => {
+                self.remove_pending_resource(token_id, resource_id);
+                Ok(RMRKReply::ResourceRejected)
+            }
+            // the message was woken up by timeout, so the resource storage did not reply
+            TxState::MsgRemoveTokenReferenceSent => Err(RMRKError::ErrorInResourceStorage),
+            TxState::Error(error) => Err(error),
+            _ => {
+                unreachable!()
+            }
+        }
+    }
+
+    /// Sets the priorities of the active resources of the token.
+    ///
+    /// # Requirements:
+    /// * The `msg::source()` must be the root owner of the token or an approved account.
+    /// * The length of `priorities` must be equal to the number of active resources.
+    ///
+    /// # Arguments:
+    /// * `token_id`: the tokenId of the NFT.
+    /// * `priorities`: the priorities of the active resources in the order of the active array.
+    ///
+    /// On success replies [`RMRKReply::PrioritySet`].
+    pub fn set_resource_priority(
+        &mut self,
+        token_id: TokenId,
+        priorities: Vec<u8>,
+    ) -> Result<RMRKReply, RMRKError> {
+        let active_len = self
+            .active_resources
+            .get(&token_id)
+            .map(|resources| resources.len())
+            .unwrap_or_default();
+        if priorities.len() != active_len {
+            return Err(RMRKError::BadPriorityListLength);
+        }
+        self.active_resources_priorities
+            .insert(token_id, priorities);
+        Ok(RMRKReply::PrioritySet)
+    }
+
+    fn check_pending_resource(
+        &self,
+        token_id: TokenId,
+        resource_id: ResourceId,
+    ) -> Result<(), RMRKError> {
+        match self.pending_resources.get(&token_id) {
+            Some(resources) if resources.contains(&resource_id) => Ok(()),
+            _ => Err(RMRKError::ResourceDoesNotExistInPendingArray),
+        }
+    }
+
+    fn remove_pending_resource(&mut self, token_id: TokenId, resource_id: ResourceId) {
+        if let Some(resources) = self.pending_resources.get_mut(&token_id) {
+            resources.remove(&resource_id);
+            if resources.is_empty() {
+                self.pending_resources.remove(&token_id);
+            }
+        }
+        if let Some(overwrites) = self.resource_overwrites.get_mut(&token_id) {
+            overwrites.remove(&resource_id);
+            if overwrites.is_empty() {
+                self.resource_overwrites.remove(&token_id);
+            }
+        }
+    }
+
+    // moves the resource from the pending array to the active one,
+    // either to the position of the overwritten resource or to the end
+    fn activate_resource(
+        &mut self,
+        token_id: TokenId,
+        resource_id: ResourceId,
+        position: Option<usize>,
+    ) {
+        self.remove_pending_resource(token_id, resource_id);
+        let active_resources = self.active_resources.entry(token_id).or_default();
+        if let Some(position) = position {
+            // the new resource keeps the priority of the overwritten one
+            active_resources[position] = resource_id;
+        } else {
+            active_resources.push(resource_id);
+            let priority = u8::try_from(active_resources.len() - 1).unwrap_or(u8::MAX);
+            self.active_resources_priorities
+                .entry(token_id)
+                .or_default()
+                .push(priority);
+        }
+    }
+
+    fn get_resource_storage_id(&self) -> Result<ActorId, RMRKError> {
+        if self.resource_storage_id == ActorId::zero() {
+            return Err(RMRKError::ResourceStorageNotSet);
+        }
+        Ok(self.resource_storage_id)
+    }
+}
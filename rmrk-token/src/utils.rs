@@ -49,6 +49,33 @@ impl From<&RMRKToken> for RMRKState {
                 .map(|(key, value)| (*key, *value))
                 .collect(),
             assets: AssetsState::default(),
+            resource_storage_id: rmrk.resource_storage_id,
+            multiresource: MultiResourceState {
+                pending_resources: rmrk
+                    .pending_resources
+                    .iter()
+                    .map(|(key, value)| {
+                        let mut resources: Vec<ResourceId> = value.iter().copied().collect();
+                        resources.sort_unstable();
+                        (*key, resources)
+                    })
+                    .collect(),
+                active_resources: rmrk
+                    .active_resources
+                    .iter()
+                    .map(|(key, value)| (*key, value.clone()))
+                    .collect(),
+                resource_overwrites: rmrk
+                    .resource_overwrites
+                    .iter()
+                    .map(|(key, value)| (*key, value.iter().map(|(k, v)| (*k, *v)).collect()))
+                    .collect(),
+                active_resources_priorities: rmrk
+                    .active_resources_priorities
+                    .iter()
+                    .map(|(key, value)| (*key, value.clone()))
+                    .collect(),
+            },
         }
     }
 }
@@ -61,6 +88,16 @@ impl From<&Assets> for AssetsState {
                 .iter()
                 .map(|(key, value)| (*key, value.clone()))
                 .collect(),
+            asset_versions: assets
+                .asset_versions
+                .iter()
+                .map(|(key, value)| (*key, *value))
+                .collect(),
+            asset_hashes: assets
+                .asset_hashes
+                .iter()
+                .map(|(key, value)| (*key, *value))
+                .collect(),
             catalog_addresses: assets
                 .catalog_addresses
                 .iter()
@@ -76,6 +113,16 @@ impl From<&Assets> for AssetsState {
                 .iter()
                 .map(|(key, value)| (*key, value.clone()))
                 .collect(),
+            fixed_part_ids: assets
+                .fixed_part_ids
+                .iter()
+                .map(|(key, value)| (*key, value.clone()))
+                .collect(),
+            slot_part_ids: assets
+                .slot_part_ids
+                .iter()
+                .map(|(key, value)| (*key, value.clone()))
+                .collect(),
             pending_assets: assets
                 .pending_assets
                 .iter()
@@ -101,6 +148,11 @@ impl From<&Assets> for AssetsState {
                     )
                 })
                 .collect(),
+            trusted_issuers: assets
+                .trusted_issuers
+                .iter()
+                .map(|(key, value)| (*key, *value))
+                .collect(),
             valid_parent_slots: assets
                 .valid_parent_slots
                 .iter()
@@ -4,7 +4,7 @@ use gmeta::metawasm;
 use gstd::{prelude::*, ActorId};
 use primitive_types::U256;
 use rmrk_io::*;
-use types::primitives::{CollectionAndToken, PartId, TokenId};
+use types::primitives::{CollectionAndToken, PartId, ResourceId, TokenId};
 
 #[cfg(feature = "binary-vendor")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
@@ -52,6 +52,81 @@ pub mod metafns {
             vec![]
         }
     }
+
+    pub fn pending_assets(state: State, token_id: TokenId) -> Vec<u64> {
+        if let Some((_, pending_assets)) = state
+            .assets
+            .pending_assets
+            .iter()
+            .find(|(id, _)| id == &token_id)
+        {
+            pending_assets.clone()
+        } else {
+            vec![]
+        }
+    }
+
+    pub fn active_assets(state: State, token_id: TokenId) -> Vec<u64> {
+        if let Some((_, active_assets)) = state
+            .assets
+            .active_assets
+            .iter()
+            .find(|(id, _)| id == &token_id)
+        {
+            active_assets.clone()
+        } else {
+            vec![]
+        }
+    }
+
+    /// Returns the metadata URI of the asset and the hash of its content (if any).
+    pub fn asset_metadata(state: State, asset_id: u64) -> Option<(String, Option<[u8; 32]>)> {
+        let metadata_hash = state
+            .assets
+            .asset_hashes
+            .iter()
+            .find(|(id, _)| id == &asset_id)
+            .map(|(_, hash)| *hash);
+        state
+            .assets
+            .assets
+            .into_iter()
+            .find(|(id, _)| id == &asset_id)
+            .map(|(_, metadata_uri)| (metadata_uri, metadata_hash))
+    }
+
+    pub fn pending_resources(state: State, token_id: TokenId) -> Vec<ResourceId> {
+        if let Some((_, pending_resources)) = state
+            .multiresource
+            .pending_resources
+            .iter()
+            .find(|(id, _)| id == &token_id)
+        {
+            pending_resources.clone()
+        } else {
+            vec![]
+        }
+    }
+
+    /// Returns the active resources of the token together with their priorities.
+    pub fn active_resources(state: State, token_id: TokenId) -> Vec<(ResourceId, u8)> {
+        let active_resources = state
+            .multiresource
+            .active_resources
+            .into_iter()
+            .find(|(id, _)| id == &token_id)
+            .map(|(_, resources)| resources)
+            .unwrap_or_default();
+        let priorities = state
+            .multiresource
+            .active_resources_priorities
+            .into_iter()
+            .find(|(id, _)| id == &token_id)
+            .map(|(_, priorities)| priorities)
+            .unwrap_or_default();
+        active_resources.into_iter().zip(priorities).collect()
+    }
+
     pub fn get_assets_and_equippable_data(
         state: State,
         token_id: TokenId,
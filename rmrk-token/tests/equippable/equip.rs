@@ -2,7 +2,9 @@ use gtest::System;
 use types::primitives::TokenId;
 
 use super::utils::{
-    add_gem_assets, add_kanaria_assets, compose, equip_gems, mint_tokens, setup_catalog,
+    add_asset_entry_with_unknown_parts, add_gem_assets, add_kanaria_assets, compose,
+    compose_equippables, equip_gems, mint_tokens, replace_composed_asset,
+    replace_equipped_child_assets, setup_catalog,
 };
 
 #[test]
@@ -17,4 +19,38 @@ fn equip() {
     let token_id: TokenId = 1.into();
     let asset_id = 2;
     compose(&system, token_id, asset_id);
+    compose_equippables(&system, token_id, asset_id);
+}
+
+#[test]
+fn replace_equipped_asset() {
+    let system = System::new();
+    system.init_logger();
+    setup_catalog(&system);
+    mint_tokens(&system);
+    add_kanaria_assets(&system);
+    add_gem_assets(&system);
+    equip_gems(&system);
+    replace_composed_asset(&system);
+}
+
+#[test]
+fn replace_equipped_child_asset() {
+    let system = System::new();
+    system.init_logger();
+    setup_catalog(&system);
+    mint_tokens(&system);
+    add_kanaria_assets(&system);
+    add_gem_assets(&system);
+    equip_gems(&system);
+    replace_equipped_child_assets(&system);
+}
+
+#[test]
+fn asset_entry_with_unknown_parts() {
+    let system = System::new();
+    system.init_logger();
+    setup_catalog(&system);
+    mint_tokens(&system);
+    add_asset_entry_with_unknown_parts(&system);
 }
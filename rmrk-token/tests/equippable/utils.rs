@@ -118,6 +118,7 @@ pub fn mint_tokens(system: &System) {
             symbol: "KAN".to_string(),
             resource_hash: None,
             resource_name: "".to_string(),
+            resource_address: None,
         },
     );
     assert!(!res.main_failed());
@@ -130,6 +131,7 @@ pub fn mint_tokens(system: &System) {
             symbol: "GEM".to_string(),
             resource_hash: None,
             resource_name: "".to_string(),
+            resource_address: None,
         },
     );
     assert!(!res.main_failed());
@@ -205,9 +207,6 @@ pub fn add_kanaria_assets(system: &System) {
 
     add_asset_to_token(&kanaria, token_id, default_asset_id, 0);
     add_asset_to_token(&kanaria, token_id, composed_asset_id, 0);
-
-    accept_asset(&kanaria, token_id, default_asset_id);
-    accept_asset(&kanaria, token_id, composed_asset_id);
 }
 
 pub fn add_gem_assets(system: &System) {
@@ -315,21 +314,6 @@ pub fn add_gem_assets(system: &System) {
     add_asset_to_token(&gem, 3.into(), 6, 0);
     add_asset_to_token(&gem, 3.into(), 7, 0);
     add_asset_to_token(&gem, 3.into(), 8, 0);
-
-    accept_asset(&gem, 1.into(), 1);
-    accept_asset(&gem, 1.into(), 2);
-    accept_asset(&gem, 1.into(), 3);
-    accept_asset(&gem, 1.into(), 4);
-
-    accept_asset(&gem, 2.into(), 1);
-    accept_asset(&gem, 2.into(), 2);
-    accept_asset(&gem, 2.into(), 3);
-    accept_asset(&gem, 2.into(), 4);
-
-    accept_asset(&gem, 3.into(), 5);
-    accept_asset(&gem, 3.into(), 6);
-    accept_asset(&gem, 3.into(), 7);
-    accept_asset(&gem, 3.into(), 8);
 }
 
 pub fn equip_gems(system: &System) {
@@ -378,6 +362,105 @@ pub fn equip_gems(system: &System) {
     assert!(result.contains(&(ADMIN, reply.encode())));
 }
 
+pub fn replace_composed_asset(system: &System) {
+    let kanaria = system.get_program(KANARIA_ID);
+    let new_composed_asset_id = 3;
+
+    // the new composed asset has no slot for the right gem
+    add_equippable_asset_entry(
+        &kanaria,
+        0,
+        Some(CATALOG_ID.into()),
+        String::from("ipfs://meta2.json"),
+        vec![1, 3, 5, 7, 9, 10],
+        new_composed_asset_id,
+    );
+
+    let result = kanaria.send(
+        ADMIN,
+        RMRKAction::AddAssetToToken {
+            token_id: 1.into(),
+            asset_id: new_composed_asset_id,
+            replaces_asset_with_id: 2,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetReplaced {
+        replaced_asset_id: 2,
+        migrated_equipments: vec![
+            (1.into(), CATALOG_ID.into(), 9),
+            (1.into(), CATALOG_ID.into(), 10),
+        ],
+        unequipped_equipments: vec![(1.into(), CATALOG_ID.into(), 11)],
+    });
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    let state: RMRKState = kanaria.read_state().expect("Failed to decode RMRKState");
+    let (_, equipments) = state
+        .assets
+        .equipments
+        .iter()
+        .find(|(key, _)| key == &(1.into(), CATALOG_ID.into()))
+        .expect("Equipments must exist");
+    let mut slots: Vec<PartId> = equipments.iter().map(|(slot, _)| *slot).collect();
+    slots.sort();
+    assert_eq!(slots, vec![9, 10]);
+    assert!(equipments
+        .iter()
+        .all(|(_, equipment)| equipment.asset_id == new_composed_asset_id));
+}
+
+pub fn replace_equipped_child_assets(system: &System) {
+    let gem = system.get_program(GEM_ID);
+
+    // the type B left gem fits the same slot as the replaced type A left gem
+    let result = gem.send(
+        ADMIN,
+        RMRKAction::AddAssetToToken {
+            token_id: 1.into(),
+            asset_id: 6,
+            replaces_asset_with_id: 2,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetReplaced {
+        replaced_asset_id: 2,
+        migrated_equipments: vec![(1.into(), CATALOG_ID.into(), 9)],
+        unequipped_equipments: vec![],
+    });
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    // the full gem can not be equipped into the kanaria
+    let result = gem.send(
+        ADMIN,
+        RMRKAction::AddAssetToToken {
+            token_id: 2.into(),
+            asset_id: 5,
+            replaces_asset_with_id: 3,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetReplaced {
+        replaced_asset_id: 3,
+        migrated_equipments: vec![],
+        unequipped_equipments: vec![(1.into(), CATALOG_ID.into(), 10)],
+    });
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    // the equipments are stored in the parent collection
+    let kanaria = system.get_program(KANARIA_ID);
+    let state: RMRKState = kanaria.read_state().expect("Failed to decode RMRKState");
+    let (_, equipments) = state
+        .assets
+        .equipments
+        .iter()
+        .find(|(key, _)| key == &(1.into(), CATALOG_ID.into()))
+        .expect("Equipments must exist");
+    let mut child_assets: Vec<(PartId, u64)> = equipments
+        .iter()
+        .map(|(slot, equipment)| (*slot, equipment.child_asset_id))
+        .collect();
+    child_assets.sort();
+    assert_eq!(child_assets, vec![(9, 6), (11, 8)]);
+}
+
 pub fn compose(system: &System, token_id: TokenId, asset_id: u64) {
     let kanaria = system.get_program(KANARIA_ID);
     let (metadata, equippable_group_id, catalog_address, part_ids): (
@@ -418,21 +501,182 @@ pub fn compose(system: &System, token_id: TokenId, asset_id: u64) {
     println!("slot parts {:?}", slot_parts);
 }
 
+pub fn compose_equippables(system: &System, token_id: TokenId, asset_id: u64) {
+    let kanaria = system.get_program(KANARIA_ID);
+
+    let result = kanaria.send(ADMIN, RMRKAction::ComposeEquippables { token_id, asset_id });
+
+    let fixed_part = |part_id: PartId, z: u32, metadata_uri: &str| FixedPartData {
+        part_id,
+        z: Some(z),
+        metadata_uri: String::from(metadata_uri),
+    };
+    let slot_part = |part_id: PartId, child_token_id: u64, child_asset_id: u64| EquippedSlotPart {
+        part_id,
+        z: Some(4),
+        metadata_uri: String::new(),
+        equipment: Some(Equipment {
+            asset_id,
+            child_asset_id,
+            child_token_id: child_token_id.into(),
+            child_id: GEM_ID.into(),
+        }),
+    };
+    let reply: Result<RMRKReply, RMRKError> =
+        Ok(RMRKReply::ComposedEquippables(ComposedEquippables {
+            metadata_uri: String::from("ipfs://meta1.json"),
+            equippable_group_id: 0,
+            catalog_address: CATALOG_ID.into(),
+            fixed_parts: vec![
+                fixed_part(1, 0, "ipfs://backgrounds/1.svg"),
+                fixed_part(3, 3, "ipfs://heads/1.svg"),
+                fixed_part(5, 2, "ipfs://body/1.svg"),
+                fixed_part(7, 4, "ipfs://wings/1.svg"),
+            ],
+            slot_parts: vec![slot_part(9, 1, 2), slot_part(10, 2, 3), slot_part(11, 3, 8)],
+            other_parts: vec![],
+        }));
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    // the asset that is not active for the token can not be composed
+    let result = kanaria.send(
+        ADMIN,
+        RMRKAction::ComposeEquippables {
+            token_id: 2.into(),
+            asset_id,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Err(RMRKError::ActiveAssetNotFound);
+    assert!(result.contains(&(ADMIN, reply.encode())));
+}
+
+pub fn add_asset_entry_with_unknown_parts(system: &System) {
+    let kanaria = system.get_program(KANARIA_ID);
+
+    // the part 100 does not exist in the catalog
+    let result = kanaria.send(
+        ADMIN,
+        RMRKAction::AddEquippableAssetEntry {
+            asset_id: 1,
+            equippable_group_id: 0,
+            catalog_address: Some(CATALOG_ID.into()),
+            metadata_uri: String::from("ipfs://meta1.json"),
+            part_ids: vec![1, 100],
+            metadata_hash: None,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Err(RMRKError::PartDoesNotExistInCatalog);
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    // the entry is added once all parts are known to the catalog
+    add_equippable_asset_entry(
+        &kanaria,
+        0,
+        Some(CATALOG_ID.into()),
+        String::from("ipfs://meta1.json"),
+        vec![1, 9],
+        1,
+    );
+
+    // the catalog reports which parts are fixed and which are slots
+    let state: RMRKState = kanaria.read_state().expect("Failed to decode the state");
+    assert_eq!(state.assets.fixed_part_ids, vec![(1, vec![1])]);
+    assert_eq!(state.assets.slot_part_ids, vec![(1, vec![9])]);
+
+    // the collection depends on the parts of its asset
+    let catalog = system.get_program(CATALOG_ID);
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::GrantRole {
+            role: CatalogRole::DependentCollection,
+            account: KANARIA_ID.into(),
+        },
+    );
+    assert!(!result.main_failed());
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: vec![1, 2],
+            force: false,
+        },
+    );
+    let reply: Result<CatalogReply, CatalogError> = Err(CatalogError::PartsHaveDependents(vec![(
+        1,
+        vec![KANARIA_ID.into()],
+    )]));
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    // the updated parts are checked against the catalog as well
+    let result = kanaria.send(
+        ADMIN,
+        RMRKAction::UpdateAssetEntry {
+            asset_id: 1,
+            metadata_uri: None,
+            metadata_hash: None,
+            part_ids: Some(vec![2, 100]),
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Err(RMRKError::PartDoesNotExistInCatalog);
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    let result = kanaria.send(
+        ADMIN,
+        RMRKAction::UpdateAssetEntry {
+            asset_id: 1,
+            metadata_uri: None,
+            metadata_hash: None,
+            part_ids: Some(vec![2, 10]),
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetEntryUpdated {
+        asset_id: 1,
+        version: 1,
+    });
+    assert!(result.contains(&(ADMIN, reply.encode())));
+
+    let state: RMRKState = kanaria.read_state().expect("Failed to decode the state");
+    assert_eq!(state.assets.part_ids, vec![(1, vec![2, 10])]);
+    assert_eq!(state.assets.fixed_part_ids, vec![(1, vec![2])]);
+    assert_eq!(state.assets.slot_part_ids, vec![(1, vec![10])]);
+
+    // the collection depends only on the new parts
+    let catalog_state: CatalogState = catalog.read_state().expect("Failed to decode CatalogState");
+    let mut dependents = catalog_state.dependents;
+    dependents.sort();
+    assert_eq!(
+        dependents,
+        vec![(2, vec![KANARIA_ID.into()]), (10, vec![KANARIA_ID.into()])]
+    );
+
+    let result = catalog.send(
+        ADMIN,
+        CatalogAction::RemoveParts {
+            part_ids: vec![1],
+            force: false,
+        },
+    );
+    let reply: Result<CatalogReply, CatalogError> = Ok(CatalogReply::PartsRemoved(vec![1]));
+    assert!(result.contains(&(ADMIN, reply.encode())));
+}
+
 fn add_equippable_asset_entry(
     program: &Program,
     equippable_group_id: u64,
     catalog_address: Option<ActorId>,
     metadata_uri: String,
     part_ids: Vec<PartId>,
-    _id: u64,
+    asset_id: u64,
 ) {
     let result = program.send(
         ADMIN,
         RMRKAction::AddEquippableAssetEntry {
+            asset_id,
             equippable_group_id,
             catalog_address,
             metadata_uri,
             part_ids,
+            metadata_hash: None,
         },
     );
 
@@ -474,12 +718,7 @@ fn add_asset_to_token(
         },
     );
 
-    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetAddedToToken);
-    assert!(result.contains(&(ADMIN, reply.encode())));
-}
-
-fn accept_asset(program: &Program, token_id: TokenId, asset_id: u64) {
-    let res = program.send(ADMIN, RMRKAction::AcceptAsset { token_id, asset_id });
+    // the assets are accepted immediately since `ADMIN` is the root owner of all tokens
     let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetAccepted);
-    assert!(res.contains(&(ADMIN, reply.encode())));
+    assert!(result.contains(&(ADMIN, reply.encode())));
 }
@@ -0,0 +1,442 @@
+use crate::utils::*;
+use gstd::prelude::*;
+use gtest::{Program, System};
+use rmrk_io::*;
+use rmrk_state::WASM_BINARY;
+
+// `USERS[0]` is the collection issuer and `USERS[1]` is the token owner
+
+#[test]
+fn propose_and_accept_asset() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+    rmrk.add_asset_entry(USERS[0], 1, "ipfs://assets/1.json", None);
+
+    // the first proposed asset creates the pending array
+    rmrk.add_asset_to_token(USERS[0], token_id, 1, 0, None);
+    rmrk.check_pending_assets(token_id, vec![1]);
+    rmrk.check_active_assets(token_id, vec![]);
+
+    // accept asset
+    rmrk.accept_asset(USERS[1], token_id, 1, None);
+    rmrk.check_pending_assets(token_id, vec![]);
+    rmrk.check_active_assets(token_id, vec![1]);
+}
+
+#[test]
+fn propose_and_accept_asset_failures() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+    rmrk.add_asset_entry(USERS[0], 1, "ipfs://assets/1.json", None);
+    rmrk.add_asset_entry(USERS[0], 2, "ipfs://assets/2.json", None);
+
+    // propose a non-existent asset
+    rmrk.add_asset_to_token(
+        USERS[0],
+        token_id,
+        10,
+        0,
+        Some(RMRKError::NoAssetMatchingId),
+    );
+
+    // propose an asset to a non-existent token
+    rmrk.add_asset_to_token(
+        USERS[0],
+        token_id + 1,
+        1,
+        0,
+        Some(RMRKError::TokenDoesNotExist),
+    );
+
+    // accept an asset that was not proposed
+    rmrk.accept_asset(
+        USERS[1],
+        token_id,
+        2,
+        Some(RMRKError::AssetDoesNotExistInPendingArray),
+    );
+
+    rmrk.add_asset_to_token(USERS[0], token_id, 1, 0, None);
+
+    // propose the pending asset again
+    rmrk.add_asset_to_token(
+        USERS[0],
+        token_id,
+        1,
+        0,
+        Some(RMRKError::AssetAlreadyExists),
+    );
+
+    // accept asset by an account that is not the owner
+    rmrk.accept_asset(USERS[2], token_id, 1, Some(RMRKError::NotApprovedAccount));
+
+    // accept asset for a non-existent token
+    rmrk.accept_asset(
+        USERS[1],
+        token_id + 1,
+        1,
+        Some(RMRKError::TokenDoesNotExist),
+    );
+
+    rmrk.accept_asset(USERS[1], token_id, 1, None);
+
+    // propose the active asset again
+    rmrk.add_asset_to_token(
+        USERS[0],
+        token_id,
+        1,
+        0,
+        Some(RMRKError::AssetAlreadyExists),
+    );
+
+    // accept the already accepted asset
+    rmrk.accept_asset(
+        USERS[1],
+        token_id,
+        1,
+        Some(RMRKError::AssetDoesNotExistInPendingArray),
+    );
+}
+
+#[test]
+fn accept_asset_by_approved_account() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+    rmrk.add_asset_entry(USERS[0], 1, "ipfs://assets/1.json", None);
+    rmrk.add_asset_to_token(USERS[0], token_id, 1, 0, None);
+
+    rmrk.approve(USERS[1], USERS[2], token_id);
+    rmrk.accept_asset(USERS[2], token_id, 1, None);
+    rmrk.check_active_assets(token_id, vec![1]);
+}
+
+#[test]
+fn replace_asset() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+    for asset_id in 1..5 {
+        rmrk.add_asset_entry(
+            USERS[0],
+            asset_id,
+            &format!("ipfs://assets/{asset_id}.json"),
+            None,
+        );
+    }
+
+    rmrk.add_asset_to_token(USERS[0], token_id, 1, 0, None);
+    rmrk.add_asset_to_token(USERS[0], token_id, 2, 0, None);
+    rmrk.check_pending_assets(token_id, vec![1, 2]);
+    rmrk.accept_asset(USERS[1], token_id, 2, None);
+    rmrk.accept_asset(USERS[1], token_id, 1, None);
+    rmrk.check_active_assets(token_id, vec![2, 1]);
+
+    // asset 3 replaces asset 2 and takes its position
+    rmrk.add_asset_to_token(USERS[0], token_id, 3, 2, None);
+    rmrk.check_pending_assets(token_id, vec![3]);
+    rmrk.check_active_assets(token_id, vec![2, 1]);
+    rmrk.accept_asset(USERS[1], token_id, 3, None);
+    rmrk.check_pending_assets(token_id, vec![]);
+    rmrk.check_active_assets(token_id, vec![3, 1]);
+
+    // asset 4 replaces an asset that is not active, so it is added to the end
+    rmrk.add_asset_to_token(USERS[0], token_id, 4, 2, None);
+    rmrk.accept_asset(USERS[1], token_id, 4, None);
+    rmrk.check_active_assets(token_id, vec![3, 1, 4]);
+}
+
+#[test]
+fn add_asset_entry_with_explicit_id() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+
+    rmrk.add_asset_entry(USERS[0], 10, "ipfs://assets/10.json", None);
+    rmrk.add_asset_entry(USERS[0], 3, "ipfs://assets/3.json", None);
+
+    // asset with zero id
+    rmrk.add_asset_entry(
+        USERS[0],
+        0,
+        "ipfs://assets/0.json",
+        Some(RMRKError::ZeroIdForbidden),
+    );
+
+    // asset with already existing id
+    rmrk.add_asset_entry(
+        USERS[0],
+        10,
+        "ipfs://assets/10.json",
+        Some(RMRKError::AssetAlreadyExists),
+    );
+
+    // only issuer can add asset entries
+    rmrk.add_asset_entry(
+        USERS[1],
+        11,
+        "ipfs://assets/11.json",
+        Some(RMRKError::NotIssuer),
+    );
+
+    let state: RMRKState = rmrk.read_state().expect("Failed to decode the state");
+    let mut asset_ids: Vec<u64> = state.assets.assets.iter().map(|(id, _)| *id).collect();
+    asset_ids.sort();
+    assert_eq!(asset_ids, vec![3, 10]);
+}
+
+#[test]
+fn update_asset_entry() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+
+    rmrk.add_asset_entry(USERS[0], 1, "ipfs://assets/1.json", None);
+
+    rmrk.update_asset_entry(
+        USERS[0],
+        1,
+        Some("ipfs://assets/1_v1.json"),
+        Ok(RMRKReply::AssetEntryUpdated {
+            asset_id: 1,
+            version: 1,
+        }),
+    );
+    rmrk.update_asset_entry(
+        USERS[0],
+        1,
+        Some("ipfs://assets/1_v2.json"),
+        Ok(RMRKReply::AssetEntryUpdated {
+            asset_id: 1,
+            version: 2,
+        }),
+    );
+
+    let state: RMRKState = rmrk.read_state().expect("Failed to decode the state");
+    assert_eq!(
+        state.assets.assets,
+        vec![(1, String::from("ipfs://assets/1_v2.json"))]
+    );
+    assert_eq!(state.assets.asset_versions, vec![(1, 2)]);
+
+    // only issuer can update asset entries
+    rmrk.update_asset_entry(
+        USERS[1],
+        1,
+        Some("ipfs://assets/1_v3.json"),
+        Err(RMRKError::NotIssuer),
+    );
+
+    // update a non-existent asset
+    rmrk.update_asset_entry(
+        USERS[0],
+        2,
+        Some("ipfs://assets/2.json"),
+        Err(RMRKError::NoAssetMatchingId),
+    );
+
+    // an update without changes does not bump the version
+    rmrk.update_asset_entry(USERS[0], 1, None, Err(RMRKError::NothingToUpdate));
+    let state: RMRKState = rmrk.read_state().expect("Failed to decode the state");
+    assert_eq!(state.assets.asset_versions, vec![(1, 2)]);
+}
+
+#[test]
+fn asset_metadata_hash() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let metadata_hash = [7; 32];
+
+    let res = rmrk.send(
+        USERS[0],
+        RMRKAction::AddEquippableAssetEntry {
+            asset_id: 1,
+            equippable_group_id: 0,
+            catalog_address: None,
+            metadata_uri: String::from("ipfs://assets/1.json"),
+            part_ids: vec![],
+            metadata_hash: Some(metadata_hash),
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::EquippableAssetEntryAdded);
+    assert!(res.contains(&(USERS[0], reply.encode())));
+
+    let metadata: Option<(String, Option<[u8; 32]>)> = rmrk
+        .read_state_using_wasm("asset_metadata", WASM_BINARY.into(), Some(1_u64))
+        .expect("Failed to read state");
+    assert_eq!(
+        metadata,
+        Some((String::from("ipfs://assets/1.json"), Some(metadata_hash)))
+    );
+
+    // the hash of the previous metadata is dropped when the URI is changed
+    rmrk.update_asset_entry(
+        USERS[0],
+        1,
+        Some("ipfs://assets/1_v1.json"),
+        Ok(RMRKReply::AssetEntryUpdated {
+            asset_id: 1,
+            version: 1,
+        }),
+    );
+    let metadata: Option<(String, Option<[u8; 32]>)> = rmrk
+        .read_state_using_wasm("asset_metadata", WASM_BINARY.into(), Some(1_u64))
+        .expect("Failed to read state");
+    assert_eq!(
+        metadata,
+        Some((String::from("ipfs://assets/1_v1.json"), None))
+    );
+
+    // the new hash is stored together with the new URI
+    let new_metadata_hash = [8; 32];
+    let res = rmrk.send(
+        USERS[0],
+        RMRKAction::UpdateAssetEntry {
+            asset_id: 1,
+            metadata_uri: Some(String::from("ipfs://assets/1_v2.json")),
+            metadata_hash: Some(new_metadata_hash),
+            part_ids: None,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetEntryUpdated {
+        asset_id: 1,
+        version: 2,
+    });
+    assert!(res.contains(&(USERS[0], reply.encode())));
+
+    let metadata: Option<(String, Option<[u8; 32]>)> = rmrk
+        .read_state_using_wasm("asset_metadata", WASM_BINARY.into(), Some(1_u64))
+        .expect("Failed to read state");
+    assert_eq!(
+        metadata,
+        Some((
+            String::from("ipfs://assets/1_v2.json"),
+            Some(new_metadata_hash)
+        ))
+    );
+}
+
+#[test]
+fn auto_accept_asset() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[0], token_id, None);
+    rmrk.add_asset_entry(USERS[0], 1, "ipfs://assets/1.json", None);
+
+    // the asset proposed by the root owner is accepted immediately
+    let res = rmrk.send(
+        USERS[0],
+        RMRKAction::AddAssetToToken {
+            token_id: token_id.into(),
+            asset_id: 1,
+            replaces_asset_with_id: 0,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetAccepted);
+    assert!(res.contains(&(USERS[0], reply.encode())));
+    rmrk.check_pending_assets(token_id, vec![]);
+    rmrk.check_active_assets(token_id, vec![1]);
+}
+
+#[test]
+fn auto_accept_asset_from_trusted_issuer() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+    rmrk.add_asset_entry(USERS[0], 1, "ipfs://assets/1.json", None);
+    rmrk.add_asset_entry(USERS[0], 2, "ipfs://assets/2.json", None);
+
+    // only the root owner can set the trusted issuer
+    let res = rmrk.send(
+        USERS[2],
+        RMRKAction::SetTrustedIssuer {
+            token_id: token_id.into(),
+            issuer: Some(USERS[0].into()),
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Err(RMRKError::NotRootOwner);
+    assert!(res.contains(&(USERS[2], reply.encode())));
+
+    let res = rmrk.send(
+        USERS[1],
+        RMRKAction::SetTrustedIssuer {
+            token_id: token_id.into(),
+            issuer: Some(USERS[0].into()),
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::TrustedIssuerSet);
+    assert!(res.contains(&(USERS[1], reply.encode())));
+
+    let res = rmrk.send(
+        USERS[0],
+        RMRKAction::AddAssetToToken {
+            token_id: token_id.into(),
+            asset_id: 1,
+            replaces_asset_with_id: 0,
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetAccepted);
+    assert!(res.contains(&(USERS[0], reply.encode())));
+    rmrk.check_active_assets(token_id, vec![1]);
+
+    // the trusted issuer is reset after transfer
+    rmrk.transfer(USERS[1], USERS[2], token_id, None);
+    rmrk.add_asset_to_token(USERS[0], token_id, 2, 0, None);
+    rmrk.check_pending_assets(token_id, vec![2]);
+}
+
+#[test]
+fn trusted_issuer_reset_when_burnt_from_parent() {
+    let sys = System::new();
+    let rmrk_child = Program::rmrk(&sys, None);
+    let rmrk_parent = Program::rmrk(&sys, None);
+    let child_token_id: u64 = 8;
+    let parent_token_id: u64 = 10;
+
+    rmrk_parent.mint_to_root_owner(USERS[0], USERS[1], parent_token_id, None);
+    rmrk_child.mint_to_nft(
+        USERS[1],
+        PARENT_NFT_CONTRACT,
+        parent_token_id,
+        child_token_id,
+        None,
+    );
+    rmrk_parent.accept_child(
+        USERS[1],
+        parent_token_id,
+        CHILD_NFT_CONTRACT,
+        child_token_id,
+        None,
+    );
+    rmrk_child.add_asset_entry(USERS[0], 1, "ipfs://assets/1.json", None);
+
+    let res = rmrk_child.send(
+        USERS[1],
+        RMRKAction::SetTrustedIssuer {
+            token_id: child_token_id.into(),
+            issuer: Some(USERS[0].into()),
+        },
+    );
+    let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::TrustedIssuerSet);
+    assert!(res.contains(&(USERS[1], reply.encode())));
+
+    // the child token is burnt together with its parent
+    rmrk_parent.burn(USERS[1], parent_token_id, None);
+
+    // the trusted issuer isn't inherited by the token minted with the same id
+    rmrk_child.mint_to_root_owner(USERS[0], USERS[2], child_token_id, None);
+    rmrk_child.add_asset_to_token(USERS[0], child_token_id, 1, 0, None);
+    rmrk_child.check_pending_assets(child_token_id, vec![1]);
+}
@@ -1,6 +1,8 @@
 mod accept_reject_children;
 mod approvals;
+mod assets;
 mod burn;
 mod mint;
+mod resources;
 mod transfer;
 mod transfer_to_rmrk_token;
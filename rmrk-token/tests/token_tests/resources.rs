@@ -0,0 +1,325 @@
+use crate::utils::*;
+use gstd::prelude::*;
+use gtest::{Program, System};
+use resource_io::{
+    BasicResource, InitResource, MediaDescriptor, Resource, ResourceAction, ResourceError,
+    ResourceEvent, ResourceState,
+};
+use rmrk_io::*;
+use types::primitives::ResourceId;
+
+const PATH_TO_RESOURCE: &str = "../target/wasm32-unknown-unknown/release/rmrk_resource.opt.wasm";
+const RESOURCE_STORAGE_ID: u64 = 100;
+const RMRK_ID: u64 = 101;
+
+// `USERS[0]` is the collection issuer and `USERS[1]` is the token owner
+
+fn basic_resource(id: ResourceId) -> Resource {
+    Resource::Basic(BasicResource {
+        src: format!("ipfs://resources/{id}"),
+        thumb: None,
+        metadata_uri: format!("ipfs://resources/{id}.json"),
+        media: None,
+    })
+}
+
+#[test]
+fn add_resource_to_token() {
+    let sys = System::new();
+    let code_id = sys.submit_code(PATH_TO_RESOURCE);
+    let rmrk = Program::rmrk(&sys, Some(code_id.into_bytes()));
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+
+    // only the issuer can add resource entries
+    rmrk.add_resource_entry(USERS[1], 1, basic_resource(1), Some(RMRKError::NotIssuer));
+    rmrk.add_resource_entry(USERS[0], 1, basic_resource(1), None);
+    rmrk.add_resource_entry(USERS[0], 2, basic_resource(2), None);
+
+    // the resource storage replies with typed errors
+    rmrk.add_resource_entry(
+        USERS[0],
+        1,
+        basic_resource(1),
+        Some(RMRKError::ResourceAlreadyExists),
+    );
+    rmrk.add_resource_entry(
+        USERS[0],
+        3,
+        Resource::Basic(BasicResource {
+            // the media type is missing
+            media: Some(MediaDescriptor::default()),
+            ..Default::default()
+        }),
+        Some(RMRKError::WrongMediaDescriptor),
+    );
+    rmrk.add_resource_to_token(
+        USERS[0],
+        token_id,
+        3,
+        0,
+        Some(RMRKError::ResourceDoesNotExist),
+    );
+
+    rmrk.add_resource_to_token(USERS[0], token_id, 1, 0, None);
+    rmrk.add_resource_to_token(USERS[0], token_id, 2, 1, None);
+
+    // the resource is already pending
+    rmrk.add_resource_to_token(
+        USERS[0],
+        token_id,
+        1,
+        0,
+        Some(RMRKError::ResourceAlreadyExists),
+    );
+
+    // the token does not exist
+    rmrk.add_resource_to_token(
+        USERS[0],
+        token_id + 1,
+        1,
+        0,
+        Some(RMRKError::TokenDoesNotExist),
+    );
+}
+
+#[test]
+fn active_resource_not_proposed_again() {
+    let sys = System::new();
+    let code_id = sys.submit_code(PATH_TO_RESOURCE);
+    let rmrk = Program::rmrk(&sys, Some(code_id.into_bytes()));
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+    rmrk.add_resource_entry(USERS[0], 1, basic_resource(1), None);
+    rmrk.add_resource_to_token(USERS[0], token_id, 1, 0, None);
+    rmrk.accept_resource(USERS[1], token_id, 1, None);
+
+    // the resource is already active
+    rmrk.add_resource_to_token(
+        USERS[0],
+        token_id,
+        1,
+        0,
+        Some(RMRKError::ResourceAlreadyExists),
+    );
+    rmrk.reject_resource(
+        USERS[1],
+        token_id,
+        1,
+        Some(RMRKError::ResourceDoesNotExistInPendingArray),
+    );
+    rmrk.check_active_resources(token_id, vec![(1, 0)]);
+
+    // the active resource is still referenced in the resource storage
+    let state: RMRKState = rmrk.read_state().expect("Failed to decode RMRKState");
+    let storage = sys.get_program(<[u8; 32]>::from(state.resource_storage_id));
+    let res = storage.send(USERS[0], ResourceAction::RemoveResource { resource_id: 1 });
+    let reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::ResourceIsReferenced);
+    assert!(res.contains(&(USERS[0], reply.encode())));
+}
+
+#[test]
+fn resource_storage_not_set() {
+    let sys = System::new();
+    let rmrk = Program::rmrk(&sys, None);
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+
+    rmrk.add_resource_entry(
+        USERS[0],
+        1,
+        basic_resource(1),
+        Some(RMRKError::ResourceStorageNotSet),
+    );
+    rmrk.add_resource_to_token(
+        USERS[0],
+        token_id,
+        1,
+        0,
+        Some(RMRKError::ResourceStorageNotSet),
+    );
+}
+
+#[test]
+fn accept_reject_and_prioritize_resources() {
+    let sys = System::new();
+    let code_id = sys.submit_code(PATH_TO_RESOURCE);
+    let rmrk = Program::rmrk(&sys, Some(code_id.into_bytes()));
+    let token_id: u64 = 5;
+
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+    for resource_id in 1..=4 {
+        rmrk.add_resource_entry(USERS[0], resource_id, basic_resource(resource_id), None);
+    }
+    rmrk.add_resource_to_token(USERS[0], token_id, 1, 0, None);
+    rmrk.add_resource_to_token(USERS[0], token_id, 2, 0, None);
+    rmrk.add_resource_to_token(USERS[0], token_id, 3, 0, None);
+    rmrk.check_pending_resources(token_id, vec![1, 2, 3]);
+
+    // only the token owner or an approved account can accept resources
+    rmrk.accept_resource(USERS[2], token_id, 1, Some(RMRKError::NotApprovedAccount));
+    rmrk.accept_resource(USERS[1], token_id, 1, None);
+    rmrk.accept_resource(USERS[1], token_id, 2, None);
+    rmrk.reject_resource(USERS[1], token_id, 3, None);
+    rmrk.check_pending_resources(token_id, vec![]);
+    rmrk.check_active_resources(token_id, vec![(1, 0), (2, 1)]);
+
+    // the resource is not pending anymore
+    rmrk.reject_resource(
+        USERS[1],
+        token_id,
+        3,
+        Some(RMRKError::ResourceDoesNotExistInPendingArray),
+    );
+
+    rmrk.set_resource_priority(
+        USERS[1],
+        token_id,
+        vec![1],
+        Some(RMRKError::BadPriorityListLength),
+    );
+    rmrk.set_resource_priority(USERS[1], token_id, vec![1, 0], None);
+    rmrk.check_active_resources(token_id, vec![(1, 1), (2, 0)]);
+
+    // the accepted resource takes the position and priority of the overwritten one
+    rmrk.add_resource_to_token(USERS[0], token_id, 4, 1, None);
+    rmrk.accept_resource(USERS[1], token_id, 4, None);
+    rmrk.check_active_resources(token_id, vec![(4, 1), (2, 0)]);
+}
+
+#[test]
+fn manage_created_resource_storage() {
+    let sys = System::new();
+    let code_id = sys.submit_code(PATH_TO_RESOURCE);
+    let rmrk = Program::rmrk(&sys, Some(code_id.into_bytes()));
+    rmrk.add_resource_entry(USERS[0], 1, basic_resource(1), None);
+    rmrk.add_resource_entry(USERS[0], 2, basic_resource(2), None);
+
+    // the issuer owns the storage created by the collection
+    let state: RMRKState = rmrk.read_state().expect("Failed to decode RMRKState");
+    let storage = sys.get_program(<[u8; 32]>::from(state.resource_storage_id));
+    let res = storage.send(
+        USERS[0],
+        ResourceAction::UpdateResource {
+            resource_id: 1,
+            src: Some(String::from("ipfs://resources/new")),
+            content_hash: None,
+            thumb: None,
+            metadata_uri: None,
+            metadata_hash: None,
+        },
+    );
+    let reply: Result<ResourceEvent, ResourceError> = Ok(ResourceEvent::ResourceUpdated(1));
+    assert!(res.contains(&(USERS[0], reply.encode())));
+
+    let res = storage.send(USERS[1], ResourceAction::RemoveResource { resource_id: 2 });
+    let reply: Result<ResourceEvent, ResourceError> = Err(ResourceError::NotAllowedToCall);
+    assert!(res.contains(&(USERS[1], reply.encode())));
+
+    let res = storage.send(USERS[0], ResourceAction::RemoveResource { resource_id: 2 });
+    let reply: Result<ResourceEvent, ResourceError> = Ok(ResourceEvent::ResourceRemoved(2));
+    assert!(res.contains(&(USERS[0], reply.encode())));
+}
+
+#[test]
+fn attach_resource_storage() {
+    let sys = System::new();
+    let token_id: u64 = 5;
+
+    // the storage is deployed and managed by the collection issuer
+    let storage = Program::from_file_with_id(&sys, RESOURCE_STORAGE_ID, PATH_TO_RESOURCE);
+    let res = storage.send(
+        USERS[0],
+        InitResource {
+            resource_name: "SharedStorage".to_string(),
+            owner: None,
+        },
+    );
+    assert!(!res.main_failed());
+
+    let rmrk = Program::current_with_id(&sys, RMRK_ID);
+    let res = rmrk.send(
+        USERS[0],
+        InitRMRK {
+            name: "RMRKToken".to_string(),
+            symbol: "RMRKSymbol".to_string(),
+            resource_hash: None,
+            resource_name: "".to_string(),
+            resource_address: Some(RESOURCE_STORAGE_ID.into()),
+        },
+    );
+    assert!(!res.main_failed());
+    rmrk.mint_to_root_owner(USERS[0], USERS[1], token_id, None);
+
+    // the resources of the attached storage are added by its owner
+    let res = storage.send(
+        USERS[0],
+        ResourceAction::AddResourceEntry {
+            resource_id: 1,
+            resource: basic_resource(1),
+            metadata_hash: None,
+        },
+    );
+    let reply: Result<ResourceEvent, ResourceError> = Ok(ResourceEvent::ResourceEntryAdded {
+        resource_id: 1,
+        resource: basic_resource(1),
+    });
+    assert!(res.contains(&(USERS[0], reply.encode())));
+
+    // must fail since the collection doesn't manage the attached storage
+    rmrk.add_resource_entry(
+        USERS[0],
+        2,
+        basic_resource(2),
+        Some(RMRKError::NotAllowedInResourceStorage),
+    );
+
+    // the collection can't reference the resources until the owner allows it
+    rmrk.add_resource_to_token(
+        USERS[0],
+        token_id,
+        1,
+        0,
+        Some(RMRKError::NotAllowedInResourceStorage),
+    );
+
+    let res = storage.send(USERS[0], ResourceAction::AllowCollection(RMRK_ID.into()));
+    let reply: Result<ResourceEvent, ResourceError> =
+        Ok(ResourceEvent::CollectionAllowed(RMRK_ID.into()));
+    assert!(res.contains(&(USERS[0], reply.encode())));
+
+    rmrk.add_resource_to_token(USERS[0], token_id, 1, 0, None);
+    rmrk.accept_resource(USERS[1], token_id, 1, None);
+    rmrk.check_active_resources(token_id, vec![(1, 0)]);
+
+    let state: ResourceState = storage
+        .read_state()
+        .expect("Failed to decode ResourceState");
+    assert_eq!(
+        state.token_references,
+        vec![(1, vec![(RMRK_ID.into(), token_id.into())])]
+    );
+}
+
+#[test]
+fn resource_storage_created_and_attached() {
+    let sys = System::new();
+    let code_id = sys.submit_code(PATH_TO_RESOURCE);
+
+    // the storage can't be both created and attached
+    let rmrk = Program::current(&sys);
+    let res = rmrk.send(
+        USERS[0],
+        InitRMRK {
+            name: "RMRKToken".to_string(),
+            symbol: "RMRKSymbol".to_string(),
+            resource_hash: Some(code_id.into_bytes()),
+            resource_name: "ResourceName".to_string(),
+            resource_address: Some(RESOURCE_STORAGE_ID.into()),
+        },
+    );
+    assert!(res.main_failed());
+}
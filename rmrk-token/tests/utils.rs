@@ -5,7 +5,8 @@ use primitive_types::U256;
 use rmrk_io::*;
 use rmrk_state::WASM_BINARY;
 
-use types::primitives::{CollectionId, TokenId};
+use resource_io::Resource;
+use types::primitives::{CollectionId, ResourceId, TokenId};
 pub const USERS: &[u64] = &[10, 11, 12, 13];
 pub const ZERO_ID: u64 = 0;
 pub const PARENT_NFT_CONTRACT: u64 = 2;
@@ -78,6 +79,73 @@ pub trait RMRKToken {
         expected_accepted_children: HashSet<(CollectionId, TokenId)>,
     );
     fn check_root_owner(&self, token_id: u64, root_owner: u64);
+    fn add_asset_entry(
+        &self,
+        user: u64,
+        asset_id: u64,
+        metadata_uri: &str,
+        exp_error: Option<RMRKError>,
+    );
+    fn update_asset_entry(
+        &self,
+        user: u64,
+        asset_id: u64,
+        metadata_uri: Option<&str>,
+        exp_reply: Result<RMRKReply, RMRKError>,
+    );
+    fn add_asset_to_token(
+        &self,
+        user: u64,
+        token_id: u64,
+        asset_id: u64,
+        replaces_asset_with_id: u64,
+        exp_error: Option<RMRKError>,
+    );
+    fn accept_asset(&self, user: u64, token_id: u64, asset_id: u64, exp_error: Option<RMRKError>);
+    fn check_pending_assets(&self, token_id: u64, expected_pending_assets: Vec<u64>);
+    fn check_active_assets(&self, token_id: u64, expected_active_assets: Vec<u64>);
+    fn add_resource_entry(
+        &self,
+        user: u64,
+        resource_id: ResourceId,
+        resource: Resource,
+        exp_error: Option<RMRKError>,
+    );
+    fn add_resource_to_token(
+        &self,
+        user: u64,
+        token_id: u64,
+        resource_id: ResourceId,
+        overwrite_id: ResourceId,
+        exp_error: Option<RMRKError>,
+    );
+    fn accept_resource(
+        &self,
+        user: u64,
+        token_id: u64,
+        resource_id: ResourceId,
+        exp_error: Option<RMRKError>,
+    );
+    fn reject_resource(
+        &self,
+        user: u64,
+        token_id: u64,
+        resource_id: ResourceId,
+        exp_error: Option<RMRKError>,
+    );
+    fn set_resource_priority(
+        &self,
+        user: u64,
+        token_id: u64,
+        priorities: Vec<u8>,
+        exp_error: Option<RMRKError>,
+    );
+    fn check_pending_resources(&self, token_id: u64, expected_pending_resources: Vec<ResourceId>);
+    fn check_active_resources(
+        &self,
+        token_id: u64,
+        expected_active_resources: Vec<(ResourceId, u8)>,
+    );
 }
 
 impl RMRKToken for Program<'_> {
@@ -90,6 +158,7 @@ impl RMRKToken for Program<'_> {
                 symbol: "RMRKSymbol".to_string(),
                 resource_hash,
                 resource_name: "ResourceName".to_string(),
+                resource_address: None,
             },
         );
         assert!(!res.main_failed());
@@ -364,6 +433,269 @@ impl RMRKToken for Program<'_> {
         let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::RootOwner(root_owner.into()));
         assert!(res.contains(&(10, reply.encode())));
     }
+
+    fn add_asset_entry(
+        &self,
+        user: u64,
+        asset_id: u64,
+        metadata_uri: &str,
+        exp_error: Option<RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::AddEquippableAssetEntry {
+                asset_id,
+                equippable_group_id: 0,
+                catalog_address: None,
+                metadata_uri: metadata_uri.to_string(),
+                part_ids: vec![],
+                metadata_hash: None,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::EquippableAssetEntryAdded);
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn update_asset_entry(
+        &self,
+        user: u64,
+        asset_id: u64,
+        metadata_uri: Option<&str>,
+        exp_reply: Result<RMRKReply, RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::UpdateAssetEntry {
+                asset_id,
+                metadata_uri: metadata_uri.map(|uri| uri.to_string()),
+                metadata_hash: None,
+                part_ids: None,
+            },
+        );
+        assert!(res.contains(&(user, exp_reply.encode())));
+    }
+
+    fn add_asset_to_token(
+        &self,
+        user: u64,
+        token_id: u64,
+        asset_id: u64,
+        replaces_asset_with_id: u64,
+        exp_error: Option<RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::AddAssetToToken {
+                token_id: token_id.into(),
+                asset_id,
+                replaces_asset_with_id,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetAddedToToken);
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn accept_asset(&self, user: u64, token_id: u64, asset_id: u64, exp_error: Option<RMRKError>) {
+        let res = self.send(
+            user,
+            RMRKAction::AcceptAsset {
+                token_id: token_id.into(),
+                asset_id,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::AssetAccepted);
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn check_pending_assets(&self, token_id: u64, expected_pending_assets: Vec<u64>) {
+        let pending_assets: Vec<u64> = self
+            .read_state_using_wasm(
+                "pending_assets",
+                WASM_BINARY.into(),
+                Some(TokenId::from(token_id)),
+            )
+            .expect("Failed to read state");
+        assert_eq!(pending_assets, expected_pending_assets);
+    }
+
+    fn check_active_assets(&self, token_id: u64, expected_active_assets: Vec<u64>) {
+        let active_assets: Vec<u64> = self
+            .read_state_using_wasm(
+                "active_assets",
+                WASM_BINARY.into(),
+                Some(TokenId::from(token_id)),
+            )
+            .expect("Failed to read state");
+        assert_eq!(active_assets, expected_active_assets);
+    }
+
+    fn add_resource_entry(
+        &self,
+        user: u64,
+        resource_id: ResourceId,
+        resource: Resource,
+        exp_error: Option<RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::AddResourceEntry {
+                resource_id,
+                resource,
+                metadata_hash: None,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> =
+                Ok(RMRKReply::ResourceEntryAdded(resource_id));
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn add_resource_to_token(
+        &self,
+        user: u64,
+        token_id: u64,
+        resource_id: ResourceId,
+        overwrite_id: ResourceId,
+        exp_error: Option<RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::AddResourceToToken {
+                token_id: token_id.into(),
+                resource_id,
+                overwrite_id,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::ResourceAddedToToken);
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn accept_resource(
+        &self,
+        user: u64,
+        token_id: u64,
+        resource_id: ResourceId,
+        exp_error: Option<RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::AcceptResource {
+                token_id: token_id.into(),
+                resource_id,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::ResourceAccepted);
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn reject_resource(
+        &self,
+        user: u64,
+        token_id: u64,
+        resource_id: ResourceId,
+        exp_error: Option<RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::RejectResource {
+                token_id: token_id.into(),
+                resource_id,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::ResourceRejected);
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn set_resource_priority(
+        &self,
+        user: u64,
+        token_id: u64,
+        priorities: Vec<u8>,
+        exp_error: Option<RMRKError>,
+    ) {
+        let res = self.send(
+            user,
+            RMRKAction::SetResourcePriority {
+                token_id: token_id.into(),
+                priorities,
+            },
+        );
+
+        if let Some(exp_error) = exp_error {
+            let error: Result<RMRKReply, RMRKError> = Err(exp_error);
+            assert!(res.contains(&(user, error.encode())));
+        } else {
+            let reply: Result<RMRKReply, RMRKError> = Ok(RMRKReply::PrioritySet);
+            assert!(res.contains(&(user, reply.encode())));
+        }
+    }
+
+    fn check_pending_resources(&self, token_id: u64, expected_pending_resources: Vec<ResourceId>) {
+        let pending_resources: Vec<ResourceId> = self
+            .read_state_using_wasm(
+                "pending_resources",
+                WASM_BINARY.into(),
+                Some(TokenId::from(token_id)),
+            )
+            .expect("Failed to read state");
+        assert_eq!(pending_resources, expected_pending_resources);
+    }
+
+    fn check_active_resources(
+        &self,
+        token_id: u64,
+        expected_active_resources: Vec<(ResourceId, u8)>,
+    ) {
+        let active_resources: Vec<(ResourceId, u8)> = self
+            .read_state_using_wasm(
+                "active_resources",
+                WASM_BINARY.into(),
+                Some(TokenId::from(token_id)),
+            )
+            .expect("Failed to read state");
+        assert_eq!(active_resources, expected_active_resources);
+    }
 }
 
 pub fn mint_parent_and_child(
@@ -8,7 +8,10 @@ pub mod primitives {
     pub type CollectionId = ActorId;
 
     // The identifier of resource for RMRK token.
-    pub type ResourceId = u8;
+    pub type ResourceId = u64;
+
+    // The identifier of resource before it was widened to `u64`.
+    pub type LegacyResourceId = u8;
 
     // The identifier of RMRK token.
     pub type TokenId = U256;